@@ -4,14 +4,20 @@ use quote;
 pub fn generate_index_struct(ast: &syn::MacroInput) -> quote::Tokens {
     let ident = &ast.ident;
     let index_ident = syn::Ident::from(format!("{}Index", ident).to_string());
+    let pipes_ident = syn::Ident::from(format!("{}Pipes", ident).to_string());
 
     let ext_id = external_id_field(ast);
     let create_external_ids = create_external_ids(ast);
     let run_query = run_query(ast);
+    let pipes = generate_pipes(ast);
+    let add_document = add_document(ast);
 
     quote!(
+        #pipes
+
         pub struct #index_ident {
             pub documents: #ident,
+            pub pipes: #pipes_ident,
             pub query_pipeline: Option<QueryPipeline<#ident>>,
             pub doc_counter: DocId,
             #ext_id
@@ -22,6 +28,7 @@ pub fn generate_index_struct(ast: &syn::MacroInput) -> quote::Tokens {
             pub fn create(base_path: PathBuf) -> Self {
                 #index_ident {
                     documents: #ident::create(&base_path),
+                    pipes: #pipes_ident::new(),
                     query_pipeline: None,
                     doc_counter: DocId::none(),
                     #create_external_ids
@@ -36,11 +43,141 @@ pub fn generate_index_struct(ast: &syn::MacroInput) -> quote::Tokens {
                 self.query_pipeline = Some(pipe);
             }
 
+            /// Number of documents added to this index.
+            /// `doc_counter` holds the last assigned `DocId` or `DocId::none()`
+            /// if no document was added yet
+            pub fn doc_count(&self) -> u32 {
+                if self.doc_counter == DocId::none() {
+                    0
+                } else {
+                    self.doc_counter.0 + 1
+                }
+            }
+
+            /// Creates a query without search terms that matches every
+            /// document of this index
+            pub fn match_all<'a>(&self) -> Query<'a> {
+                Query::new("").match_all(self.doc_count())
+            }
+
+            #add_document
+
             #run_query
         }
     )
 }
 
+/// Fields that are filled by a pipeline, i.e. not marked with `#[no_pipe]`
+fn piped_fields(ast: &syn::MacroInput) -> Vec<&syn::Field> {
+    if let syn::Body::Struct(ref variant_data) = ast.body {
+        variant_data.fields()
+            .iter()
+            .filter(|field| !field.attrs.iter().any(|attribute| attribute.name() == "no_pipe"))
+            .collect()
+    } else {
+        panic!("derive(PerlinDocument) only implemented for Structs!");
+    }
+}
+
+/// The term type `T` of a `Field<T>` or `Fields<T>`
+fn get_term_type(ty: &syn::Ty) -> &syn::Ty {
+    if let syn::Ty::Path(_, ref path) = *ty {
+        if let syn::PathParameters::AngleBracketed(ref parameters) =
+            path.segments.last().unwrap().parameters {
+            if let Some(term_type) = parameters.types.first() {
+                return term_type;
+            }
+        }
+    }
+    panic!("Fields have to be of type Field<T> or Fields<T>!");
+}
+
+fn pipeline_setter(field: &syn::Field) -> syn::Ident {
+    syn::Ident::from(format!("set_{}_pipeline", field.ident.as_ref().unwrap()))
+}
+
+// IdentPipes {
+//    field: Option<Pipeline<T, Ident>>,
+//    ...
+// }
+fn generate_pipes(ast: &syn::MacroInput) -> quote::Tokens {
+    let ident = &ast.ident;
+    let pipes_ident = syn::Ident::from(format!("{}Pipes", ident).to_string());
+    let fields = piped_fields(ast);
+    let pipe_fields = fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        let term_type = get_term_type(&field.ty);
+        quote!(pub #field_ident: Option<Pipeline<#term_type, #ident>>)
+    });
+    let pipe_idents = fields.iter().map(|field| &field.ident);
+
+    quote!(
+        pub struct #pipes_ident {
+            #(#pipe_fields,)*
+        }
+
+        impl #pipes_ident {
+            pub fn new() -> Self {
+                #pipes_ident {
+                    #(#pipe_idents: None,)*
+                }
+            }
+        }
+    )
+}
+
+fn add_document(ast: &syn::MacroInput) -> quote::Tokens {
+    let ident = &ast.ident;
+    let fields = piped_fields(ast);
+    let setters = fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        let setter = pipeline_setter(field);
+        let term_type = get_term_type(&field.ty);
+        quote!(
+            pub fn #setter(&mut self, pipe: Pipeline<#term_type, #ident>) {
+                self.pipes.#field_ident = Some(pipe);
+            }
+        )
+    });
+    let run_pipes = fields.iter().map(|field| {
+        let field_ident = &field.ident;
+        let name = field_ident.as_ref().unwrap().to_string();
+        quote!(
+            #name => {
+                if let Some(ref pipe) = self.pipes.#field_ident {
+                    pipe(doc_id, &mut self.documents, value.as_ref());
+                }
+            }
+        )
+    });
+    let (ext_id_param, push_ext_id) = if let Some(ext_id_type) =
+        get_external_id_type(&ast.attrs) {
+        (quote!(, external_id: #ext_id_type),
+         quote!(self.external_ids.push((doc_id, external_id));))
+    } else {
+        (quote!(), quote!())
+    };
+
+    quote!(
+        #(#setters)*
+
+        /// Runs every value through the pipeline of its field.
+        /// Fields without a pipeline are skipped
+        pub fn add_document(&mut self, key_values: &[(Cow<str>, Cow<str>)] #ext_id_param) -> DocId {
+            let doc_id = DocId(self.doc_count());
+            self.doc_counter = doc_id;
+            #push_ext_id
+            for &(ref key, ref value) in key_values {
+                match key.as_ref() {
+                    #(#run_pipes)*
+                    _ => panic!("{} is not a field with a pipeline!", key),
+                }
+            }
+            doc_id
+        }
+    )
+}
+
 fn run_query(ast: &syn::MacroInput) -> quote::Tokens {
     if let Some(ext_id_type) = get_external_id_type(&ast.attrs) {
         quote!{
@@ -49,7 +186,7 @@ fn run_query(ast: &syn::MacroInput) -> quote::Tokens {
                 use perlin_core::index::posting::Posting;
                     if let Some(ref query_pipe) = self.query_pipeline {
                         let ops = query_pipe(&self.documents, &query);
-                    QueryResultIterator::new(ops, query.filter, &self.external_ids)
+                    QueryResultIterator::new(ops, query, &self.external_ids)
                 } else {
                     panic!("Query Pipe not set!");
                 }
@@ -57,9 +194,10 @@ fn run_query(ast: &syn::MacroInput) -> quote::Tokens {
         }
     } else {
         quote!{
-            pub fn run_query<'a>(&'a self, query: Query<'a>) -> Operand<'a> {
+            pub fn run_query<'a>(&'a self, query: Query<'a>) -> PostingResultIterator<'a> {
                 if let Some(ref query_pipe) = self.query_pipeline {
-                    query_pipe(&self.documents, query)
+                    let ops = query_pipe(&self.documents, &query);
+                    PostingResultIterator::new(ops, query)
                 } else {
                     panic!("Query Pipe not set!");
                 }
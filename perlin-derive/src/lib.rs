@@ -13,7 +13,7 @@ use perlin_document::generate_perlin_document_impl;
 
 use proc_macro::TokenStream;
 
-#[proc_macro_derive(PerlinDocument, attributes(ExternalId, no_pipe, filter))]
+#[proc_macro_derive(PerlinDocument, attributes(ExternalId, no_pipe, filter, doc_values))]
 pub fn perlin_document(input: TokenStream) -> TokenStream {
     // Standard procedure when it comes to custom derive
     // See https://doc.rust-lang.org/book/procedural-macros.html
@@ -55,8 +55,11 @@ fn impl_perlin_document(ast: &syn::MacroInput) -> quote::Tokens {
             use_parent_crate!(document_index::Pipeline);
             use_parent_crate!(document_index::QueryPipeline);
             use_parent_crate!(document_index::QueryResultIterator);
+            use_parent_crate!(document_index::PostingResultIterator);
             use_parent_crate!(query::Operand);
             use_parent_crate!(query::Query);
+            use_parent_crate!(field::FieldSupplement);
+            use_parent_crate!(field::DocValues);
             
             use perlin_core::index::posting::{PostingIterator, DocId};
             use perlin_core::index::vocabulary::TermId;
@@ -31,13 +31,34 @@ fn generate_index_creations(fields: &[syn::Field]) -> Vec<quote::Tokens> {
     for field in fields {
         let type_ident = get_type_ident(&field.ty).unwrap();
         let ident = &field.ident;
-        result.push(quote!(
-            #ident: #type_ident::new()
-        ));            
+        if has_attribute(&field.attrs, "doc_values") {
+            // FieldSupplement only exists on Field<T>. Fields<T> creates its
+            // fields at runtime via add_field
+            if type_ident != "Field" {
+                panic!("#[doc_values] is only supported on Field<T>, but {} is a {}!",
+                       ident.as_ref().unwrap(),
+                       type_ident);
+            }
+            result.push(quote!(
+                #ident: {
+                    let mut field = #type_ident::new();
+                    field.supplement = FieldSupplement::DocValues(DocValues::new());
+                    field
+                }
+            ));
+        } else {
+            result.push(quote!(
+                #ident: #type_ident::new()
+            ));
+        }
     }
     result
 }
 
+fn has_attribute(attributes: &[syn::Attribute], name: &str) -> bool {
+    attributes.iter().any(|attribute| attribute.name() == name)
+}
+
 
 fn get_type_ident(ty: &syn::Ty) -> Option<&syn::Ident> {
     if let &syn::Ty::Path(_, ref path) = ty {
@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
+use std::hash::Hash;
 
-use query::{Query, Operand, WeightingOperator};
+use query::{Query, Operand, WeightingOperator, DocFilter};
+use field::{Field, SortOrder, sort_by_doc_values};
 use perlin_core::utils::seeking_iterator::PeekableSeekable;
 use perlin_core::index::posting::{Posting, DocId};
 
@@ -8,14 +10,67 @@ pub type Pipeline<Out, T> = Box<Fn(DocId, &mut T, &str) -> PhantomData<Out> + Sy
 pub type QueryPipeline<T> =
     Box<for<'r> Fn(&'r T, &Query<'r>) -> Vec<PeekableSeekable<Operand<'r>>> + Sync + Send>;
 
-pub struct QueryResultIterator<'a, T: 'a>(WeightingOperator<'a>, &'a [(DocId, T)]);
+/// Yields the postings of all documents matching a query.
+/// Besides the operands of the query pipeline all clauses of the `Query`
+/// are applied.
+pub struct PostingResultIterator<'a>(WeightingOperator<'a>, Vec<DocFilter<'a>>);
+
+impl<'a> PostingResultIterator<'a> {
+    pub fn new(mut ops: Vec<PeekableSeekable<Operand<'a>>>, query: Query<'a>) -> Self {
+        let Query { filter, mut should, must_not, doc_filter, .. } = query;
+        ops.append(&mut should);
+        PostingResultIterator(WeightingOperator::create(ops, filter, must_not), doc_filter)
+    }
+}
+
+impl<'a> Iterator for PostingResultIterator<'a> {
+    type Item = Posting;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(Posting(doc_id)) = self.0.next() {
+            if self.1.iter().all(|filter| filter(doc_id)) {
+                return Some(Posting(doc_id));
+            }
+        }
+        None
+    }
+}
+
+impl<'a> AsRef<WeightingOperator<'a>> for PostingResultIterator<'a> {
+    fn as_ref(&self) -> &WeightingOperator<'a> {
+        &self.0
+    }
+}
+
+impl<'a> AsMut<WeightingOperator<'a>> for PostingResultIterator<'a> {
+    fn as_mut(&mut self) -> &mut WeightingOperator<'a> {
+        &mut self.0
+    }
+}
+
+/// Like `PostingResultIterator` but yields the external ids of the documents
+pub struct QueryResultIterator<'a, T: 'a>(PostingResultIterator<'a>, &'a [(DocId, T)]);
 
 impl<'a, T: 'a + Clone> QueryResultIterator<'a, T> {
     pub fn new(ops: Vec<PeekableSeekable<Operand<'a>>>,
-               filters: Vec<PeekableSeekable<Operand<'a>>>,
+               query: Query<'a>,
                ext_ids: &'a [(DocId, T)])
                -> Self {
-        QueryResultIterator(WeightingOperator::create(ops, filters), ext_ids)
+        QueryResultIterator(PostingResultIterator::new(ops, query), ext_ids)
+    }
+}
+
+impl<'a, T: 'a + Clone> QueryResultIterator<'a, T> {
+    /// Collects all results and sorts them by their value in `field`.
+    /// See `field::sort_by_doc_values`
+    pub fn sort_by_doc_values<TTerm>(self, field: &Field<TTerm>, order: SortOrder) -> Vec<T>
+        where TTerm: Hash + Eq + Ord + Clone
+    {
+        let ext_ids = self.1;
+        sort_by_doc_values(self.0, field, order)
+            .into_iter()
+            .map(|Posting(doc_id)| external_id(ext_ids, doc_id))
+            .collect()
     }
 }
 
@@ -23,27 +78,27 @@ impl<'a, T: 'a + Clone> Iterator for QueryResultIterator<'a, T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(Posting(doc_id)) = self.0.next() {
-            if let Ok(index) = self.1.binary_search_by_key(&doc_id, |&(d_id, _)| d_id) {
-                Some(self.1[index].1.clone())
-            } else {
-                panic!("DocId unkown!");
-            }
-        } else {
-            None
-        }
+        self.0.next().map(|Posting(doc_id)| external_id(self.1, doc_id))
     }
 }
 
 impl<'a, T: 'a> AsRef<WeightingOperator<'a>> for QueryResultIterator<'a, T> {
     fn as_ref(&self) -> &WeightingOperator<'a> {
-        &self.0
+        self.0.as_ref()
     }
 }
 
 impl<'a, T: 'a> AsMut<WeightingOperator<'a>> for QueryResultIterator<'a, T> {
     fn as_mut(&mut self) -> &mut WeightingOperator<'a> {
-        &mut self.0
+        self.0.as_mut()
+    }
+}
+
+fn external_id<T: Clone>(ext_ids: &[(DocId, T)], doc_id: DocId) -> T {
+    if let Ok(index) = ext_ids.binary_search_by_key(&doc_id, |&(d_id, _)| d_id) {
+        ext_ids[index].1.clone()
+    } else {
+        panic!("DocId unkown!");
     }
 }
 
@@ -66,13 +121,23 @@ mod tests {
         number: Field<u64>,
         #[no_pipe]
         emails: Field<usize>,
+        #[no_pipe]
+        #[doc_values]
+        year: Field<u64>,
     }
 
-    use language::{Stemmer, LowercaseFilter, WhitespaceTokenizer};
+    use language::{Stemmer, LowercaseFilter, WhitespaceTokenizer, KeywordTokenizer, FnTokenizer,
+                   PipelineBucket, PorterStemmer, SnowballStemmer, Language,
+                   AsciiFoldingFilter,
+                   StopwordFilter,
+                   CamelCaseFilter,
+                   SoundexFilter,
+                   UnicodeNormalizationFilter, NormalizationForm,
+                   PatternReplaceFilter};
     use language::integers::NumberFilter;
     use std::borrow::Cow;
     use perlin_core::index::posting::Posting;
-    use query::{Query, ChainingOperator};
+    use query::{Query, ChainingOperator, Operand};
 
 
     fn create_and_fill_index(name: &str) -> TestIndex {
@@ -176,6 +241,401 @@ mod tests {
                    vec![Posting(DocId(2))]);
     }
 
+    #[test]
+    fn excluding_query() {
+        let t = create_and_fill_index("doc_index/excluding_query");
+        let excluding =
+            Query::new("flew").filter_by(ChainingOperator::MustNot,
+                                         t.documents.number.query_atom(&2567).1);
+
+        assert_eq!(t.run_query(excluding).collect::<Vec<_>>(),
+                   vec![Posting(DocId(0)), Posting(DocId(1))]);
+    }
+
+    #[test]
+    fn fn_tokenizer_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/fn_tokenizer_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      FnTokenizer(|input, emit| for token in input.split(',') {
+                                          emit(token.trim())
+                                      })
+                                      > LowercaseFilter));
+        t.set_query_pipeline(query_pipeline!(
+            FnTokenizer(|input, emit| for token in input.split(',') { emit(token.trim()) })
+                > LowercaseFilter
+                > [Any in text]));
+        t.add_document(&[(Cow::from("text"), Cow::from("New York, Berlin"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Paris"))]);
+        t.commit();
+        should_yield(&t, "new york", &[0]);
+        should_yield(&t, "berlin", &[0]);
+        should_yield(&t, "paris", &[1]);
+        should_yield(&t, "york", &[]);
+    }
+
+    #[test]
+    fn fuzzy_query() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/fuzzy_query"));
+        t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > LowercaseFilter));
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Fuzzy(2) in text]));
+        t.add_document(&[(Cow::from("text"), Cow::from("Information retrieval systems"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Retrieving documents from archives"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Relational databases store tables"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Search engines rank results"))]);
+        t.commit();
+        should_yield(&t, "retreival", &[0]);
+        should_yield(&t, "databse", &[2]);
+        should_yield(&t, "retrieval", &[0]);
+        should_yield(&t, "pizza", &[]);
+    }
+
+    #[test]
+    fn fuzzy_query_expansions() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/fuzzy_query_expansions"));
+        t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > LowercaseFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("cat"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("cart"))]);
+        // 100 terms within two edits of any two char term
+        let short_terms = (0..100).map(|i| format!("{}{}", (b'a' + i / 10) as char, i % 10))
+            .collect::<Vec<_>>()
+            .join(" ");
+        t.add_document(&[(Cow::from("text"), Cow::from(short_terms))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Fuzzy(1) in text]));
+        should_yield(&t, "cat", &[0, 1]);
+        // Only the closest term
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Fuzzy(1, 1) in text]));
+        should_yield(&t, "cat", &[0]);
+        // All expansions of a term end up in one operand
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Fuzzy(2) in text]));
+        should_yield(&t, "xy", &[2]);
+        let ops = (t.query_pipeline.as_ref().unwrap())(&t.documents, &Query::new("xy"));
+        assert_eq!(ops.len(), 1);
+    }
+
+    #[test]
+    fn prefix_query() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/prefix_query"));
+        t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > LowercaseFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("information retrieval"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("informal talk"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("informal chat"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("inner workings"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Prefix in text]));
+        should_yield(&t, "inform", &[0, 1, 2]);
+        should_yield(&t, "in", &[0, 1, 2, 3]);
+        should_yield(&t, "xyz", &[]);
+        // Only the most frequent term
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Prefix(1) in text]));
+        should_yield(&t, "inform", &[1, 2]);
+    }
+
+    #[test]
+    fn keyword_query() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/keyword_query"));
+        t.set_title_pipeline(pipeline!(title KeywordTokenizer > LowercaseFilter));
+        t.add_document(&[(Cow::from("title"), Cow::from("New York"))]);
+        t.add_document(&[(Cow::from("title"), Cow::from("Newark"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Any in title]));
+        should_yield(&t, "new", &[]);
+        should_yield(&t, "new york", &[]);
+        t.set_query_pipeline(query_pipeline!(
+            KeywordTokenizer
+                > LowercaseFilter
+                > [Any in title]));
+        should_yield(&t, "New York", &[0]);
+        should_yield(&t, "new", &[]);
+    }
+
+    fn create_and_fill_tee_index(name: &str) -> TestIndex {
+        let mut t = TestIndex::create(create_test_dir(name));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > LowercaseFilter
+                                      > Tee { Stemmer(Algorithm::English) > [title] }));
+        t.add_document(&[(Cow::from("text"), Cow::from("Birds flying"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Birds sleeping"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Unicorns"))]);
+        t.commit();
+        t
+    }
+
+    #[test]
+    fn tee_pipeline() {
+        let mut t = create_and_fill_tee_index("doc_index/tee_pipeline");
+        // Unstemmed terms end up in text
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Any in text]));
+        should_yield(&t, "flying", &[0]);
+        should_yield(&t, "fli", &[]);
+        // Stemmed terms in title
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [Any in title]));
+        should_yield(&t, "fli", &[0]);
+        should_yield(&t, "flying", &[]);
+    }
+
+    #[test]
+    fn tee_query_pipeline() {
+        let mut t = create_and_fill_tee_index("doc_index/tee_query_pipeline");
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > Tee { Stemmer(Algorithm::English) > [Any in title] }
+                > [Any in text]));
+        let ops = (t.query_pipeline.as_ref().unwrap())(&t.documents, &Query::new("Flying"));
+        let terms = ops.iter()
+            .map(|op| match *op.inner() {
+                Operand::Term(_, _, ref term, _) => term.clone(),
+                ref op => panic!("Unexpected operand {:?}", op),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(terms, vec!["fli".to_string(), "flying".to_string()]);
+        should_yield(&t, "flying", &[0]);
+    }
+
+    #[test]
+    fn boosting_query() {
+        let t = create_and_fill_index("doc_index/boosting_query");
+        let boosted =
+            Query::new("flew").filter_by(ChainingOperator::May,
+                                         t.documents.number.query_atom(&2567).1);
+
+        // The boosted document comes first. The others still match "flew"
+        assert_eq!(t.run_query(boosted).collect::<Vec<_>>(),
+                   vec![Posting(DocId(2)), Posting(DocId(0)), Posting(DocId(1))]);
+    }
+
+    #[test]
+    fn boosting_query_without_terms() {
+        let t = create_and_fill_index("doc_index/boosting_query_without_terms");
+        let boosted =
+            Query::new("ocean").filter_by(ChainingOperator::May,
+                                          t.documents.number.query_atom(&2567).1);
+
+        // Without a Must clause, matching only the May clause is enough
+        let mut results = t.run_query(boosted).collect::<Vec<_>>();
+        results.sort();
+        assert_eq!(results, vec![Posting(DocId(1)), Posting(DocId(2))]);
+    }
+
+    #[test]
+    fn match_all_query() {
+        let empty = TestIndex::create(create_test_dir("doc_index/match_all_query_empty"));
+        assert_eq!(empty.doc_count(), 0);
+
+        let t = create_and_fill_index("doc_index/match_all_query");
+        let all = t.match_all();
+        let filtered = t.match_all().filter_by(ChainingOperator::Must,
+                                               t.documents.number.query_atom(&2567).1);
+
+        assert_eq!(t.doc_count(), 3);
+        assert_eq!(t.run_query(all).collect::<Vec<_>>(),
+                   vec![Posting(DocId(0)), Posting(DocId(1)), Posting(DocId(2))]);
+        assert_eq!(t.run_query(filtered).collect::<Vec<_>>(),
+                   vec![Posting(DocId(2))]);
+    }
+
+    #[test]
+    fn range_query() {
+        let mut t = create_and_fill_index("doc_index/range_query");
+        t.documents.year.put(DocId(0), 1990);
+        t.documents.year.put(DocId(1), 2000);
+        // DocId(2) has no year
+        t.commit();
+        let inclusive = Query::new("flew").filter_range(&t.documents.year, 1990, 2000);
+        let upper = Query::new("flew").filter_range(&t.documents.year, 1991, 2000);
+        let empty = Query::new("flew").filter_range(&t.documents.year, 2001, 3000);
+
+        assert_eq!(t.run_query(inclusive).collect::<Vec<_>>(),
+                   vec![Posting(DocId(0)), Posting(DocId(1))]);
+        assert_eq!(t.run_query(upper).collect::<Vec<_>>(),
+                   vec![Posting(DocId(1))]);
+        assert_eq!(t.run_query(empty).collect::<Vec<_>>(), Vec::<Posting>::new());
+    }
+
+    #[test]
+    fn porter_stemmer_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/porter_stemmer_pipeline"));
+        t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > LowercaseFilter > PorterStemmer));
+        t.add_document(&[(Cow::from("text"), Cow::from("She runs daily"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("He walked home"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > PorterStemmer
+                > [All in text]));
+        should_yield(&t, "running", &[0]);
+        should_yield(&t, "walking", &[1]);
+    }
+
+    #[test]
+    fn german_snowball_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/german_snowball_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > LowercaseFilter
+                                      > SnowballStemmer(Language::German)));
+        t.add_document(&[(Cow::from("text"), Cow::from("Die Katzen schlafen im Garten"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Der Hund spielt"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > SnowballStemmer(Language::German)
+                > [All in text]));
+        should_yield(&t, "Katze", &[0]);
+        should_yield(&t, "Gärten", &[0]);
+        should_yield(&t, "Hunde", &[1]);
+    }
+
+    #[test]
+    fn ascii_folding_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/ascii_folding_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > LowercaseFilter
+                                      > AsciiFoldingFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("Le Café"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("The cafe"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > AsciiFoldingFilter
+                > [All in text]));
+        should_yield(&t, "cafe", &[0, 1]);
+        should_yield(&t, "CAFÉ", &[0, 1]);
+    }
+
+    #[test]
+    fn stopword_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/stopword_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > LowercaseFilter
+                                      > StopwordFilter(vec!["the".to_string(), "a".to_string()])));
+        t.add_document(&[(Cow::from("text"), Cow::from("The ocean"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("A lake"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > StopwordFilter(vec!["the".to_string(), "a".to_string()])
+                > [All in text]));
+        let ops = (t.query_pipeline.as_ref().unwrap())(&t.documents, &Query::new("the"));
+        assert!(ops.is_empty());
+        should_yield(&t, "the ocean", &[0]);
+        should_yield(&t, "a lake", &[1]);
+    }
+
+    #[test]
+    fn camel_case_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/camel_case_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > CamelCaseFilter
+                                      > LowercaseFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("fn getUserName()"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("fn setPassword()"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > LowercaseFilter
+                > [All in text]));
+        should_yield(&t, "user", &[0]);
+        should_yield(&t, "password", &[1]);
+    }
+
+    #[test]
+    fn soundex_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/soundex_pipeline"));
+        t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > SoundexFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("Robert"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Rupert"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("Alice"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > SoundexFilter
+                > [All in text]));
+        should_yield(&t, "Robert", &[0, 1]);
+        should_yield(&t, "rupert", &[0, 1]);
+    }
+
+    #[test]
+    fn unicode_normalization_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/unicode_normalization_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      WhitespaceTokenizer
+                                      > UnicodeNormalizationFilter(NormalizationForm::NFC)
+                                      > LowercaseFilter));
+        // Precomposed é
+        t.add_document(&[(Cow::from("text"), Cow::from("Caf\u{e9}"))]);
+        // e followed by a combining acute accent
+        t.add_document(&[(Cow::from("text"), Cow::from("Cafe\u{301}"))]);
+        t.commit();
+        t.set_query_pipeline(query_pipeline!(
+            WhitespaceTokenizer
+                > UnicodeNormalizationFilter(NormalizationForm::NFC)
+                > LowercaseFilter
+                > [All in text]));
+        should_yield(&t, "caf\u{e9}", &[0, 1]);
+        should_yield(&t, "cafe\u{301}", &[0, 1]);
+    }
+
+    #[test]
+    fn pattern_replace_pipeline() {
+        let mut t = TestIndex::create(create_test_dir("doc_index/pattern_replace_pipeline"));
+        t.set_text_pipeline(pipeline!(text
+                                      PatternReplaceFilter("[-_]", " ")
+                                      > WhitespaceTokenizer
+                                      > LowercaseFilter));
+        t.add_document(&[(Cow::from("text"), Cow::from("E-Mail_Address"))]);
+        t.add_document(&[(Cow::from("text"), Cow::from("email"))]);
+        t.commit();
+        let mut terms = t.documents.text.iterate_terms()
+            .map(|(term, _)| term.as_str())
+            .collect::<Vec<_>>();
+        terms.sort();
+        assert_eq!(terms, vec!["address", "e", "email", "mail"]);
+        t.set_query_pipeline(query_pipeline!(
+            PatternReplaceFilter("[-_]", " ")
+                > WhitespaceTokenizer
+                > LowercaseFilter
+                > [All in text]));
+        should_yield(&t, "mail", &[0]);
+        should_yield(&t, "e_mail", &[0]);
+    }
+
     #[test]
     fn iterate_filters() {
         let mut t = create_and_fill_index("doc_index/iterate_filters");
@@ -186,4 +646,60 @@ mod tests {
         assert_eq!(t.frequent_terms_number().map(|(df, t, _)| (df, *t)).collect::<Vec<_>>(),
                    vec![(5, 10), (3, 125), (1, 2567)]);
     }
+
+    // Every derive generates a perlin_impl module, so each test document
+    // needs a module of its own
+    mod external_ids {
+        use std::borrow::Cow;
+
+        use field::{Field, SortOrder};
+        use language::{LowercaseFilter, WhitespaceTokenizer, PipelineBucket};
+        use query::Query;
+        use test_utils::create_test_dir;
+
+        #[derive(PerlinDocument)]
+        #[ExternalId(u64)]
+        pub struct ExtTest {
+            text: Field<String>,
+            #[no_pipe]
+            #[doc_values]
+            year: Field<u64>,
+        }
+
+        fn create_and_fill_index(name: &str) -> ExtTestIndex {
+            let mut t = ExtTestIndex::create(create_test_dir(name));
+            t.set_text_pipeline(pipeline!(text WhitespaceTokenizer > LowercaseFilter));
+            t.set_query_pipeline(query_pipeline!(
+                WhitespaceTokenizer
+                    > LowercaseFilter
+                    > [All in text]));
+            let doc_id = t.add_document(&[(Cow::from("text"), Cow::from("Birds flew"))], 100);
+            t.documents.year.put(doc_id, 1990);
+            let doc_id = t.add_document(&[(Cow::from("text"), Cow::from("Unicorns flew"))], 200);
+            t.documents.year.put(doc_id, 2000);
+            // No year
+            t.add_document(&[(Cow::from("text"), Cow::from("Dragons flew"))], 300);
+            t.add_document(&[(Cow::from("text"), Cow::from("Dragons slept"))], 400);
+            t.commit();
+            t
+        }
+
+        #[test]
+        fn range_query() {
+            let t = create_and_fill_index("doc_index/external_ids/range_query");
+            let query = Query::new("flew").filter_range(&t.documents.year, 1990, 2000);
+            assert_eq!(t.run_query(query).collect::<Vec<_>>(), vec![100, 200]);
+        }
+
+        #[test]
+        fn sort_results() {
+            let t = create_and_fill_index("doc_index/external_ids/sort_results");
+            assert_eq!(t.run_query(Query::new("flew"))
+                           .sort_by_doc_values(&t.documents.year, SortOrder::Descending),
+                       vec![200, 100, 300]);
+            assert_eq!(t.run_query(Query::new("flew"))
+                           .sort_by_doc_values(&t.documents.year, SortOrder::Ascending),
+                       vec![100, 200, 300]);
+        }
+    }
 }
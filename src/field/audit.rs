@@ -0,0 +1,31 @@
+use perlin_core::index::posting::DocId;
+
+/// Records the terms a document produced after running through the full
+/// pipeline. Helps to find out why a query does not match a document.
+///
+/// Documents are expected to be indexed in ascending `DocId` order.
+pub struct AuditLog<T>(Vec<(DocId, Vec<T>)>);
+
+impl<T> AuditLog<T> {
+    pub fn record(&mut self, doc_id: DocId, term: T) {
+        if let Some(&mut (last_id, ref mut terms)) = self.0.last_mut() {
+            if last_id == doc_id {
+                terms.push(term);
+                return;
+            }
+        }
+        self.0.push((doc_id, vec![term]));
+    }
+
+    pub fn terms(&self, doc_id: DocId) -> Option<&[T]> {
+        if let Ok(index) = self.0.binary_search_by_key(&doc_id, |&(d_id, _)| d_id) {
+            Some(&self.0[index].1)
+        } else {
+            None
+        }
+    }
+
+    pub fn new() -> Self {
+        AuditLog(vec![])
+    }
+}
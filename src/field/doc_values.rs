@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+use std::hash::Hash;
+
+use perlin_core::index::posting::{Posting, DocId};
+
+use field::{Field, FieldSupplement};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Column oriented storage of a fields value.
+/// Values are stored densely and indexed by `DocId`. That way sorting and
+/// aggregating over a field does not need a lookup per document.
+/// If a document contains more than one term for this field, the first one
+/// wins.
+pub struct DocValues<T>(Vec<Option<T>>);
+
+impl<T: Clone> DocValues<T> {
+    pub fn put(&mut self, doc_id: DocId, value: &T) {
+        let index = doc_id.0 as usize;
+        if self.0.len() <= index {
+            self.0.resize(index + 1, None);
+        }
+        if self.0[index].is_none() {
+            self.0[index] = Some(value.clone());
+        }
+    }
+
+    pub fn get(&self, doc_id: DocId) -> Option<&T> {
+        self.0.get(doc_id.0 as usize).and_then(|v| v.as_ref())
+    }
+
+    pub fn new() -> Self {
+        DocValues(vec![])
+    }
+}
+
+/// Sorts the postings yielded by `results` by their value in `field`.
+/// Documents without a value are sorted to the end regardless of `order`.
+/// To sort results with external ids use `QueryResultIterator::sort_by_doc_values`.
+///
+/// Panics if `field` was not created with doc values.
+pub fn sort_by_doc_values<T, I>(results: I, field: &Field<T>, order: SortOrder) -> Vec<Posting>
+    where T: Hash + Eq + Ord + Clone,
+          I: Iterator<Item = Posting>
+{
+    let doc_values = if let FieldSupplement::DocValues(ref doc_values) = field.supplement {
+        doc_values
+    } else {
+        panic!("Field {} has no doc values!", field.name);
+    };
+    let mut postings = results.collect::<Vec<_>>();
+    postings.sort_by(|a, b| {
+        match (doc_values.get(a.0), doc_values.get(b.0)) {
+            (Some(a), Some(b)) => {
+                match order {
+                    SortOrder::Ascending => a.cmp(b),
+                    SortOrder::Descending => b.cmp(a),
+                }
+            }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    });
+    postings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use perlin_core::index::posting::{Posting, DocId};
+    use field::Field;
+    use language::PipelineBucket;
+
+    use test_utils::create_test_dir;
+
+    #[derive(PerlinDocument)]
+    pub struct DocValuesTest {
+        #[no_pipe]
+        #[doc_values]
+        price: Field<u64>,
+    }
+
+    fn create_and_fill_index(name: &str) -> DocValuesTestIndex {
+        let mut t = DocValuesTestIndex::create(create_test_dir(name));
+        t.documents.price.put(DocId(0), 30);
+        t.documents.price.put(DocId(1), 10);
+        t.documents.price.put(DocId(2), 20);
+        // DocId(3) has no price
+        t.commit();
+        t
+    }
+
+    fn results() -> ::std::vec::IntoIter<Posting> {
+        (0..4).map(|id| Posting(DocId(id))).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn ascending() {
+        let t = create_and_fill_index("doc_values/ascending");
+        assert_eq!(sort_by_doc_values(results(), &t.documents.price, SortOrder::Ascending),
+                   vec![Posting(DocId(1)), Posting(DocId(2)), Posting(DocId(0)),
+                        Posting(DocId(3))]);
+    }
+
+    #[test]
+    fn descending() {
+        let t = create_and_fill_index("doc_values/descending");
+        assert_eq!(sort_by_doc_values(results(), &t.documents.price, SortOrder::Descending),
+                   vec![Posting(DocId(0)), Posting(DocId(2)), Posting(DocId(1)),
+                        Posting(DocId(3))]);
+    }
+
+    #[test]
+    fn missing_values_last() {
+        let t = create_and_fill_index("doc_values/missing_values_last");
+        let reversed = results().rev().collect::<Vec<_>>().into_iter();
+        for &order in &[SortOrder::Ascending, SortOrder::Descending] {
+            let sorted = sort_by_doc_values(reversed.clone(), &t.documents.price, order);
+            assert_eq!(sorted.last(), Some(&Posting(DocId(3))));
+        }
+    }
+
+    #[test]
+    fn first_value_wins() {
+        let mut doc_values = DocValues::new();
+        doc_values.put(DocId(1), &30);
+        doc_values.put(DocId(1), &5);
+        assert_eq!(doc_values.get(DocId(0)), None);
+        assert_eq!(doc_values.get(DocId(1)), Some(&30));
+        assert_eq!(doc_values.get(DocId(2)), None);
+    }
+}
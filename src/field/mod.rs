@@ -10,14 +10,19 @@ use language::PipelineBucket;
 
 mod filter;
 mod hierarchy;
+mod doc_values;
+mod audit;
 
 pub use field::filter::Filter;
 pub use field::hierarchy::Hierarchy;
+pub use field::doc_values::{DocValues, SortOrder, sort_by_doc_values};
+pub use field::audit::AuditLog;
 
 pub enum FieldSupplement<T> {
     None,
     Filter(Filter<T>),
     Hierarchy(Hierarchy<T>),
+    DocValues(DocValues<T>),
 }
 
 pub struct Field<T: Hash + Eq> {
@@ -25,6 +30,7 @@ pub struct Field<T: Hash + Eq> {
     pub name: String,
     pub term_doc_ratio: f32,
     pub supplement: FieldSupplement<T>,
+    audit_log: Option<AuditLog<T>>,
 }
 
 impl<T: Hash + Eq + Ord + Clone + 'static> Field<T> {
@@ -34,12 +40,33 @@ impl<T: Hash + Eq + Ord + Clone + 'static> Field<T> {
             filter.commit(&self.index);
         }
     }
+
+    /// Starts recording every term that is put into this field.
+    /// As this stores a copy of every term, it should only be enabled while
+    /// debugging pipelines.
+    pub fn enable_audit_log(&mut self) {
+        if self.audit_log.is_none() {
+            self.audit_log = Some(AuditLog::new());
+        }
+    }
+
+    /// Returns the terms that were put into this field for `doc_id`.
+    /// Returns `None` if the audit log is disabled or the document is unknown.
+    pub fn audited_terms(&self, doc_id: DocId) -> Option<&[T]> {
+        self.audit_log.as_ref().and_then(|log| log.terms(doc_id))
+    }
 }
 
 impl<TTerm> PipelineBucket<TTerm> for Field<TTerm>
-    where TTerm: Hash + Eq + Ord
+    where TTerm: Hash + Eq + Ord + Clone
 {
     fn put(&mut self, doc_id: DocId, term: TTerm) {
+        if let FieldSupplement::DocValues(ref mut doc_values) = self.supplement {
+            doc_values.put(doc_id, &term);
+        }
+        if let Some(ref mut audit_log) = self.audit_log {
+            audit_log.record(doc_id, term.clone());
+        }
         self.index.index_term(doc_id, term);
     }
 }
@@ -90,6 +117,7 @@ impl<T: Hash + Eq + Ord + Clone + 'static> Fields<T> {
                                    index: Index::new(page_cache, SharedVocabulary::new()),
                                    term_doc_ratio: 1.0,
                                    supplement,
+                                   audit_log: None,
                                });
             return Ok(());
         }
@@ -112,6 +140,47 @@ mod tests {
     pub struct FilterTest {
         body: Fields<String>,
     }
+
+    // Every derive generates a perlin_impl module, so each test document
+    // needs a module of its own
+    mod audit {
+        use perlin_core::index::posting::DocId;
+        use field::Field;
+
+        use rust_stemmers::Algorithm;
+
+        use std::borrow::Cow;
+        use language::{Stemmer, LowercaseFilter, WhitespaceTokenizer};
+        use test_utils::create_test_dir;
+
+        #[derive(PerlinDocument)]
+        pub struct AuditTest {
+            text: Field<String>,
+            title: Field<String>,
+        }
+
+        #[test]
+        fn audit_log() {
+            let mut t = AuditTestIndex::create(create_test_dir("field/audit_log"));
+            t.set_text_pipeline(pipeline!(text
+                                          WhitespaceTokenizer
+                                          > LowercaseFilter
+                                          > Stemmer(Algorithm::English)));
+            t.set_title_pipeline(pipeline!(title
+                                           WhitespaceTokenizer
+                                           > LowercaseFilter));
+            t.documents.text.enable_audit_log();
+            t.add_document(&[(Cow::from("text"), Cow::from("Running dogs")),
+                             (Cow::from("title"), Cow::from("Dogs"))]);
+            t.commit();
+
+            assert_eq!(t.documents.text.audited_terms(DocId(0)),
+                       Some(&["run".to_string(), "dog".to_string()][..]));
+            assert_eq!(t.documents.text.audited_terms(DocId(1)), None);
+            // Not enabled
+            assert_eq!(t.documents.title.audited_terms(DocId(0)), None);
+        }
+    }
     // pub use self::perlin_impl::FilterTestIndex;
     // mod perlin_impl {
     //     use super::*;
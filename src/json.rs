@@ -0,0 +1,50 @@
+//! Streaming serialization of query results to JSON.
+//! Only available with the `json` feature.
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json;
+
+/// Writes every item of `results` as element of a JSON array to `writer`.
+/// Items are serialized one at a time, so memory stays bounded no matter how
+/// many results are written.
+///
+/// Returns the number of written items.
+pub fn write_json_results<W, I, T>(writer: &mut W, results: I) -> serde_json::Result<usize>
+    where W: Write,
+          I: Iterator<Item = T>,
+          T: Serialize
+{
+    writer.write_all(b"[").map_err(serde_json::Error::io)?;
+    let mut count = 0;
+    for result in results {
+        if count > 0 {
+            writer.write_all(b",").map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut *writer, &result)?;
+        count += 1;
+    }
+    writer.write_all(b"]").map_err(serde_json::Error::io)?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_results() {
+        let mut buffer = Vec::new();
+        let count = write_json_results(&mut buffer, Vec::<u32>::new().into_iter()).unwrap();
+        assert_eq!(count, 0);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[]");
+    }
+
+    #[test]
+    fn multiple_results() {
+        let mut buffer = Vec::new();
+        let count = write_json_results(&mut buffer, vec!["a", "b\"", "c"].into_iter()).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(String::from_utf8(buffer).unwrap(), r#"["a","b\"","c"]"#);
+    }
+}
@@ -0,0 +1,131 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Maps accented latin characters to their ASCII equivalents.
+/// "café" becomes "cafe", "straße" becomes "strasse".
+/// Characters of other scripts are passed through unchanged.
+pub struct AsciiFoldingFilter<TCallback> {
+    callback: TCallback,
+}
+
+impl<TCallback> AsciiFoldingFilter<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        AsciiFoldingFilter { callback: callback }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for AsciiFoldingFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.callback.apply(fold(input));
+    }
+}
+
+impl<TCallback> CanApply<String> for AsciiFoldingFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        if input.is_ascii() {
+            self.callback.apply(input);
+        } else {
+            self.callback.apply(fold(&input));
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for AsciiFoldingFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+fn fold(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    for c in input.chars() {
+        match fold_char(c) {
+            Some(folded) => result.push_str(folded),
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+fn fold_char(c: char) -> Option<&'static str> {
+    let folded = match c {
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' | 'Ā' | 'Ă' | 'Ą' => "A",
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Ĉ' | 'Ċ' | 'Č' => "C",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È' | 'É' | 'Ê' | 'Ë' | 'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => "G",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'Ĥ' | 'Ħ' => "H",
+        'ĥ' | 'ħ' => "h",
+        'Ì' | 'Í' | 'Î' | 'Ï' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ĵ' => "J",
+        'ĵ' => "j",
+        'Ķ' => "K",
+        'ķ' => "k",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => "L",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'Ñ' | 'Ń' | 'Ņ' | 'Ň' => "N",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' | 'Ō' | 'Ŏ' | 'Ő' => "O",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => "S",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'Ţ' | 'Ť' | 'Ŧ' => "T",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ù' | 'Ú' | 'Û' | 'Ü' | 'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ŵ' => "W",
+        'ŵ' => "w",
+        'Ý' | 'Ŷ' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn folding() {
+        let words = [("café", "cafe"),
+                     ("straße", "strasse"),
+                     ("Ærøskøbing", "AEroskobing"),
+                     ("niño", "nino"),
+                     ("plain", "plain"),
+                     ("Москва", "Москва")];
+        for &(word, folded) in &words {
+            let mut terms = Collector::new();
+            AsciiFoldingFilter::create(&mut terms).apply(word);
+            assert_eq!(terms.terms(), &[folded]);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Splits tokens on case transitions and digit boundaries.
+/// "getUserName" results in "get", "User", "Name" and "HTMLParser2" in
+/// "HTML", "Parser", "2". Non alphanumeric chars are dropped.
+///
+/// Put a `LowercaseFilter` behind it to match "user" against "getUserName".
+pub struct CamelCaseFilter<TCallback> {
+    emit_original: bool,
+    callback: TCallback,
+}
+
+impl<TCallback> CamelCaseFilter<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        CamelCaseFilter {
+            emit_original: false,
+            callback: callback,
+        }
+    }
+
+    /// Also passes on the original token if it was split
+    pub fn with_original(mut self) -> Self {
+        self.emit_original = true;
+        self
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for CamelCaseFilter<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        let parts = split_camel_case(input);
+        if self.emit_original && parts.len() > 1 {
+            self.callback.apply(input);
+        }
+        for part in parts {
+            self.callback.apply(part);
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for CamelCaseFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+fn split_camel_case(input: &str) -> Vec<&str> {
+    let chars = input.char_indices().collect::<Vec<_>>();
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if let Some(start) = start.take() {
+                parts.push(&input[start..offset]);
+            }
+            continue;
+        }
+        if let Some(part_start) = start {
+            let previous = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+            let is_boundary =
+                // camelCase
+                (previous.is_lowercase() && c.is_uppercase()) ||
+                // HTMLParser: The last uppercase char belongs to the next word
+                (previous.is_uppercase() && c.is_uppercase() &&
+                 next.map_or(false, |next| next.is_lowercase())) ||
+                // Digits
+                (previous.is_numeric() != c.is_numeric());
+            if is_boundary {
+                parts.push(&input[part_start..offset]);
+                start = Some(offset);
+            }
+        } else {
+            start = Some(offset);
+        }
+    }
+    if let Some(start) = start {
+        parts.push(&input[start..]);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::LowercaseFilter;
+    use test_utils::Collector;
+
+    #[test]
+    fn splits() {
+        assert_eq!(split_camel_case("getUserName"), vec!["get", "User", "Name"]);
+        assert_eq!(split_camel_case("PerlinIndex"), vec!["Perlin", "Index"]);
+        assert_eq!(split_camel_case("HTMLParser"), vec!["HTML", "Parser"]);
+        assert_eq!(split_camel_case("parseHTML"), vec!["parse", "HTML"]);
+        assert_eq!(split_camel_case("utf8Decoder"), vec!["utf", "8", "Decoder"]);
+        assert_eq!(split_camel_case("snake_case"), vec!["snake", "case"]);
+        assert_eq!(split_camel_case("plain"), vec!["plain"]);
+    }
+
+    #[test]
+    fn pipeline() {
+        let mut terms = Collector::new();
+        CamelCaseFilter::create(LowercaseFilter::create(&mut terms))
+            .with_original()
+            .apply("getUserName");
+        assert_eq!(terms.terms(), &["getusername", "get", "user", "name"]);
+    }
+}
@@ -0,0 +1,133 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Tokenizer for text containing Chinese, Japanese or Korean.
+/// These scripts do not separate words by whitespace, so runs of CJK chars
+/// are split into overlapping bigrams: "日本語" results in "日本", "本語".
+/// A run consisting of a single char is passed on as unigram.
+///
+/// Everything else is tokenized like `AlphaNumericTokenizer` does, so
+/// whitespace and punctuation separate tokens. "AI技術, ok!" results in
+/// "AI", "技術", "ok".
+pub struct CjkBigramTokenizer<TCallback> {
+    callback: TCallback,
+}
+
+impl<TCallback> CjkBigramTokenizer<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        CjkBigramTokenizer { callback: callback }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for CjkBigramTokenizer<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        // Start of the current run and whether it is CJK
+        let mut run: Option<(usize, bool)> = None;
+        for (offset, c) in input.char_indices() {
+            let class = if is_cjk(c) {
+                Some(true)
+            } else if !c.is_alphanumeric() {
+                None
+            } else {
+                Some(false)
+            };
+            match (run, class) {
+                (Some((_, run_is_cjk)), Some(is_cjk)) if run_is_cjk == is_cjk => {}
+                (Some((start, run_is_cjk)), _) => {
+                    self.emit(&input[start..offset], run_is_cjk);
+                    run = class.map(|is_cjk| (offset, is_cjk));
+                }
+                (None, _) => run = class.map(|is_cjk| (offset, is_cjk)),
+            }
+        }
+        if let Some((start, run_is_cjk)) = run {
+            self.emit(&input[start..], run_is_cjk);
+        }
+    }
+}
+
+impl<'a, TCallback> CjkBigramTokenizer<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    fn emit(&mut self, run: &'a str, is_cjk: bool) {
+        if !is_cjk {
+            self.callback.apply(run);
+            return;
+        }
+        let mut boundaries = run.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+        boundaries.push(run.len());
+        if boundaries.len() == 2 {
+            self.callback.apply(run);
+            return;
+        }
+        for i in 0..boundaries.len() - 2 {
+            self.callback.apply(&run[boundaries[i]..boundaries[i + 2]]);
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for CjkBigramTokenizer<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    match c as u32 {
+        // Hangul Jamo
+        0x1100..=0x11FF |
+        // Hiragana, Katakana
+        0x3040..=0x30FF |
+        // Hangul Compatibility Jamo
+        0x3130..=0x318F |
+        // Katakana Phonetic Extensions
+        0x31F0..=0x31FF |
+        // CJK Unified Ideographs Extension A
+        0x3400..=0x4DBF |
+        // CJK Unified Ideographs
+        0x4E00..=0x9FFF |
+        // Hangul Syllables
+        0xAC00..=0xD7AF |
+        // CJK Compatibility Ideographs
+        0xF900..=0xFAFF |
+        // Halfwidth Katakana
+        0xFF66..=0xFF9F |
+        // CJK Unified Ideographs Extension B and following
+        0x20000..=0x2FA1F => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn bigrams() {
+        let mut terms = Collector::new();
+        CjkBigramTokenizer::create(&mut terms).apply("日本語");
+        assert_eq!(terms.terms(), &["日本", "本語"]);
+    }
+
+    #[test]
+    fn mixed_scripts() {
+        let mut terms = Collector::new();
+        CjkBigramTokenizer::create(&mut terms).apply("AI技術 and 東京。京");
+        assert_eq!(terms.terms(), &["AI", "技術", "and", "東京", "京"]);
+    }
+
+    #[test]
+    fn latin_punctuation() {
+        let mut terms = Collector::new();
+        CjkBigramTokenizer::create(&mut terms).apply("hello, 世界! (it's)");
+        assert_eq!(terms.terms(), &["hello", "世界", "it", "s"]);
+    }
+}
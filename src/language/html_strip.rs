@@ -0,0 +1,162 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Removes HTML markup and decodes entities before passing the text on.
+/// "<p>Hello &amp; <b>world</b></p>" becomes "Hello & world".
+///
+/// Tags are replaced by whitespace, so an inline tag inside a word splits it.
+/// The content of `script` and `style` elements is dropped.
+/// A "<" that is not followed by a letter, "/" or "!" is kept as text, so is
+/// an unclosed tag at the end of the input.
+pub struct HtmlStripFilter<TCallback> {
+    callback: TCallback,
+}
+
+impl<TCallback> HtmlStripFilter<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        HtmlStripFilter { callback: callback }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for HtmlStripFilter<TCallback>
+    where TCallback: for<'b> CanApply<&'b str>
+{
+    type Output = <TCallback as CanApply<&'a str>>::Output;
+    fn apply(&mut self, input: &'a str) {
+        let text = strip_html(input);
+        self.callback.apply(&text);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for HtmlStripFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+fn strip_html(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while !rest.is_empty() {
+        if starts_with_tag(rest) {
+            let end = match rest.find('>') {
+                Some(end) => end,
+                // Unclosed tag. Keep it as text
+                None => {
+                    result.push_str(rest);
+                    break;
+                }
+            };
+            let tag = rest[1..end].trim().to_ascii_lowercase();
+            rest = &rest[end + 1..];
+            if tag == "script" || tag.starts_with("script ") || tag == "style" ||
+               tag.starts_with("style ") {
+                let name = if tag.starts_with("script") { "script" } else { "style" };
+                rest = skip_element_content(rest, name);
+            }
+            if !result.is_empty() && !result.ends_with(char::is_whitespace) {
+                result.push(' ');
+            }
+        } else if rest.starts_with('&') {
+            match decode_entity(rest) {
+                Some((decoded, length)) => {
+                    result.push(decoded);
+                    rest = &rest[length..];
+                }
+                None => {
+                    result.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        } else if rest.starts_with('<') {
+            // A "<" that does not start a tag like in "a < b"
+            result.push('<');
+            rest = &rest[1..];
+        } else {
+            let end = rest.find(|c| c == '<' || c == '&').unwrap_or(rest.len());
+            result.push_str(&rest[..end]);
+            rest = &rest[end..];
+        }
+    }
+    let trimmed_length = result.trim_end().len();
+    result.truncate(trimmed_length);
+    result
+}
+
+/// A tag starts with "<" followed by a letter, "/" or "!"
+fn starts_with_tag(input: &str) -> bool {
+    let mut chars = input.chars();
+    chars.next() == Some('<') &&
+    chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+/// Returns the input after the closing tag of `name`
+fn skip_element_content<'a>(input: &'a str, name: &str) -> &'a str {
+    let closing_tag = format!("</{}", name);
+    match input.to_ascii_lowercase().find(&closing_tag) {
+        Some(start) => {
+            match input[start..].find('>') {
+                Some(end) => &input[start + end + 1..],
+                None => "",
+            }
+        }
+        None => "",
+    }
+}
+
+/// Decodes the entity at the beginning of `input`.
+/// Returns the decoded char and the length of the entity in bytes.
+fn decode_entity(input: &str) -> Option<(char, usize)> {
+    // Entities are short. Do not scan through the whole text
+    let end = input.bytes().take(11).position(|b| b == b';')?;
+    let entity = &input[1..end];
+    let decoded = match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+            u32::from_str_radix(&entity[2..], 16).ok().and_then(::std::char::from_u32)?
+        }
+        _ if entity.starts_with('#') => {
+            entity[1..].parse::<u32>().ok().and_then(::std::char::from_u32)?
+        }
+        _ => return None,
+    };
+    Some((decoded, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::WhitespaceTokenizer;
+    use test_utils::Collector;
+
+    #[test]
+    fn strip() {
+        assert_eq!(strip_html("<p>Hello &amp; <b>world</b></p>"), "Hello & world");
+        assert_eq!(strip_html("it&#39;s &lt;3 &#x41;"), "it's <3 A");
+        assert_eq!(strip_html("a<script>var x = '<b>';</script>b"), "a b");
+        assert_eq!(strip_html("unclosed <b"), "unclosed <b");
+        assert_eq!(strip_html("if a < b then c"), "if a < b then c");
+        assert_eq!(strip_html("a<b>c<d"), "a c<d");
+        assert_eq!(strip_html("<!-- comment -->text"), "text");
+        assert_eq!(strip_html("fish & chips &unknown;"), "fish & chips &unknown;");
+        assert_eq!(strip_html("&ampersand is not an entity;"), "&ampersand is not an entity;");
+        assert_eq!(strip_html("&#x10FFFF;&#1114112;"), "\u{10FFFF}&#1114112;");
+    }
+
+    #[test]
+    fn pipeline() {
+        let mut terms = Collector::new();
+        HtmlStripFilter::create(WhitespaceTokenizer::create(&mut terms))
+            .apply("<p>Hello <b>world</b></p>");
+        assert_eq!(terms.terms(), &["Hello", "world"]);
+    }
+}
@@ -53,6 +53,72 @@ impl<'a, TStringCallback, TNumberCallback> ToOperands<'a>
 
 
 
+/// Normalizes numbers before they are handed to a `NumberFilter`.
+/// Strips grouping separators and zero fractions, so "1,000", "1_000" and
+/// "1000.0" all result in "1000".
+/// Tokens that do not look like numbers are passed on untouched.
+pub struct NormalizeNumberFilter<TCallback> {
+    callback: TCallback,
+}
+
+impl<TCallback> NormalizeNumberFilter<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        NormalizeNumberFilter { callback: callback }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for NormalizeNumberFilter<TCallback>
+    where TCallback: for<'b> CanApply<&'b str>
+{
+    type Output = <TCallback as CanApply<&'a str>>::Output;
+    fn apply(&mut self, input: &'a str) {
+        if let Some(normalized) = normalize_number(input) {
+            self.callback.apply(&normalized);
+        } else {
+            self.callback.apply(input);
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for NormalizeNumberFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+/// Returns `None` if `input` is no number or does not need normalization
+fn normalize_number(input: &str) -> Option<String> {
+    let (integer, fraction) = match input.find('.') {
+        Some(index) => (&input[..index], Some(&input[index + 1..])),
+        None => (input, None),
+    };
+    if let Some(fraction) = fraction {
+        if fraction.is_empty() || !fraction.chars().all(|c| c == '0') {
+            return None;
+        }
+    }
+    let mut groups = integer.split(|c| c == ',' || c == '_');
+    let first = groups.next()?;
+    if first.is_empty() || !first.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut normalized = first.to_string();
+    for group in groups {
+        if group.len() != 3 || !group.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        normalized.push_str(group);
+    }
+    if normalized.len() == input.len() {
+        // Nothing to normalize
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
 pub struct ToUsize<TCallback> {
     callback: TCallback,
 }
@@ -74,3 +140,28 @@ impl<'a, TCallback> CanApply<&'a str> for ToUsize<TCallback>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn normalize_numbers() {
+        let mut numbers = Collector::new();
+        let mut strings = Collector::new();
+        {
+            let mut filter =
+                NormalizeNumberFilter::create(NumberFilter::create(&mut numbers, &mut strings));
+            filter.apply("1,000");
+            filter.apply("1_000_000");
+            filter.apply("12.0");
+            filter.apply("42");
+            filter.apply("1,2");
+            filter.apply("12.5");
+            filter.apply("hello");
+        }
+        assert_eq!(numbers.terms(), &["1000", "1000000", "12", "42"]);
+        assert_eq!(strings.terms(), &["1,2", "12.5", "hello"]);
+    }
+}
@@ -0,0 +1,89 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Drops tokens whose length in chars is not within `[min, max]`.
+/// Pass `usize::MAX` as `max` to disable the upper bound.
+pub struct LengthFilter<TCallback> {
+    min: usize,
+    max: usize,
+    callback: TCallback,
+}
+
+impl<TCallback> LengthFilter<TCallback> {
+    pub fn create(min: usize, max: usize, callback: TCallback) -> Self {
+        LengthFilter {
+            min: min,
+            max: max,
+            callback: callback,
+        }
+    }
+
+    fn accepts(&self, input: &str) -> bool {
+        let length = input.chars().count();
+        length >= self.min && length <= self.max
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for LengthFilter<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        if self.accepts(input) {
+            self.callback.apply(input);
+        }
+    }
+}
+
+impl<TCallback> CanApply<String> for LengthFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        if self.accepts(&input) {
+            self.callback.apply(input);
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for LengthFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::repeat;
+    use std::usize;
+
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn filters_by_length() {
+        let mut terms = Collector::new();
+        let long_token = repeat("a").take(50).collect::<String>();
+        {
+            let mut filter = LengthFilter::create(2, 20, &mut terms);
+            filter.apply("a");
+            filter.apply(long_token.as_str());
+            filter.apply("hello");
+            // Chars, not bytes
+            filter.apply("ü");
+        }
+        assert_eq!(terms.terms(), &["hello"]);
+    }
+
+    #[test]
+    fn unbounded() {
+        let mut terms = Collector::new();
+        let long_token = repeat("a").take(50).collect::<String>();
+        LengthFilter::create(2, usize::MAX, &mut terms).apply(long_token.as_str());
+        assert_eq!(terms.terms(), &[long_token]);
+    }
+}
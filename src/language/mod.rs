@@ -9,9 +9,31 @@ use query::{Operand, ToOperands};
 mod stemmers;
 pub mod integers;
 mod stopword_filter;
+mod oversized;
+mod ngrams;
+mod ascii_folding;
+mod normalization;
+mod length_filter;
+mod shingles;
+mod pattern_replace;
+mod html_strip;
+mod camel_case;
+mod soundex;
+mod cjk;
 
 pub use language::stopword_filter::StopwordFilter;
-pub use language::stemmers::Stemmer;
+pub use language::stemmers::{Stemmer, PorterStemmer, SnowballStemmer, Language};
+pub use language::oversized::{TruncateFilter, HashOversizedFilter};
+pub use language::ngrams::{NGramTokenizer, EdgeNGramTokenizer};
+pub use language::ascii_folding::AsciiFoldingFilter;
+pub use language::normalization::{UnicodeNormalizationFilter, NormalizationForm};
+pub use language::length_filter::LengthFilter;
+pub use language::shingles::ShingleFilter;
+pub use language::pattern_replace::PatternReplaceFilter;
+pub use language::html_strip::HtmlStripFilter;
+pub use language::camel_case::CamelCaseFilter;
+pub use language::soundex::SoundexFilter;
+pub use language::cjk::CjkBigramTokenizer;
 
 /// The single central trait of the push-based splittable pipeline!
 /// Any element in it can be called passing a typed and generic input and a common value
@@ -56,6 +78,50 @@ impl<'a, TCB> ToOperands<'a> for AlphaNumericTokenizer<TCB>
 }
 
 
+/// Tokenizer that is defined by a closure.
+/// The closure gets the input and a function to emit tokens to. Tokens may
+/// borrow from the input.
+///
+/// `FnTokenizer::create(|input, emit| for t in input.split(',') { emit(t) }, callback)`
+///
+/// In the pipeline macros the closure is passed as parameter:
+/// `FnTokenizer(|input, emit| ...) > LowercaseFilter`
+pub struct FnTokenizer<TFn, TCallback> {
+    tokenize: TFn,
+    callback: TCallback,
+}
+
+impl<TFn, TCallback> FnTokenizer<TFn, TCallback>
+    where TFn: for<'b> FnMut(&'b str, &mut FnMut(&'b str))
+{
+    pub fn create(tokenize: TFn, callback: TCallback) -> Self {
+        FnTokenizer {
+            tokenize: tokenize,
+            callback: callback,
+        }
+    }
+}
+
+impl<'a, TFn, TCallback> CanApply<&'a str> for FnTokenizer<TFn, TCallback>
+    where TFn: for<'b> FnMut(&'b str, &mut FnMut(&'b str)),
+          TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        let callback = &mut self.callback;
+        (self.tokenize)(input, &mut |token| callback.apply(token));
+    }
+}
+
+impl<'a, TFn, TCallback> ToOperands<'a> for FnTokenizer<TFn, TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+
 pub struct Debugger<TCallback>
 {
     callback: TCallback,
@@ -119,6 +185,36 @@ impl<'a, TCallback> ToOperands<'a> for WhitespaceTokenizer<TCallback>
     }
 }
 
+/// Passes the whole input on as a single token.
+/// Use it for fields like ids, tags or country codes that must not be split.
+pub struct KeywordTokenizer<TCallback>
+{
+    callback: TCallback,
+}
+
+impl<TCallback> KeywordTokenizer<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        KeywordTokenizer {
+            callback: callback
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for KeywordTokenizer<TCallback>
+    where TCallback: CanApply<&'a str> {
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.callback.apply(input);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for KeywordTokenizer<TCallback>
+    where TCallback: ToOperands<'a> {
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
 pub struct LowercaseFilter<TCallback>
 {
     callback: TCallback,
@@ -141,6 +237,15 @@ impl<'a, TCallback> CanApply<&'a str> for LowercaseFilter<TCallback>
     }
 }
 
+impl<TCallback> CanApply<String> for LowercaseFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        self.callback.apply(input.to_lowercase())
+    }
+}
+
 impl<'a, TCallback> ToOperands<'a> for LowercaseFilter<TCallback>
     where TCallback: ToOperands<'a> {
 
@@ -178,6 +283,52 @@ impl<'a, TTerm, TBucket> CanApply<TTerm> for Funnel<'a, TTerm, TBucket>
     }
 }
 
+/// Fans the pipeline out. Every input is applied to both `left` and `right`.
+///
+/// In the pipeline macros the branch is written as
+/// `Tee { Stemmer(Algorithm::English) > [stemmed_field] }`.
+/// The rest of the pipeline continues as usual.
+/// Every branch has to end in a field of its own, `[field]` in `pipeline!`
+/// and `[OP in field]` in `query_pipeline!`. In `pipeline!` it must not be a
+/// field the rest of the pipeline feeds, as both would borrow it mutably.
+pub struct Tee<TLeft, TRight> {
+    left: TLeft,
+    right: TRight,
+}
+
+impl<TLeft, TRight> Tee<TLeft, TRight> {
+    pub fn create(left: TLeft, right: TRight) -> Self {
+        Tee {
+            left: left,
+            right: right,
+        }
+    }
+}
+
+impl<T, TLeft, TRight> CanApply<T> for Tee<TLeft, TRight>
+    where T: Clone,
+          TLeft: CanApply<T>,
+          TRight: CanApply<T>
+{
+    type Output = TLeft::Output;
+
+    fn apply(&mut self, input: T) {
+        self.left.apply(input.clone());
+        self.right.apply(input);
+    }
+}
+
+impl<'a, TLeft, TRight> ToOperands<'a> for Tee<TLeft, TRight>
+    where TLeft: ToOperands<'a>,
+          TRight: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        let mut result = self.left.to_operands();
+        result.append(&mut self.right.to_operands());
+        result
+    }
+}
+
 #[macro_export]
 macro_rules! inner_pipeline {
     (;$INDEX:ident; ;$doc_id:expr; ;$field:ident;
@@ -185,6 +336,20 @@ macro_rules! inner_pipeline {
         // >
         inner_pipeline!(;$INDEX; ;$doc_id; ;$field; $($x)*)
     };
+    (;$INDEX:ident; ;$doc_id:expr; ;$field:ident;
+     Tee { $($branch:tt)* } $($x:tt)*) =>
+    // ;doc_id; ;field_id; Tee { Elements > [other_field] }
+    // The branch must end in a field the rest does not borrow
+    {
+        $crate::language::Tee::create(
+            inner_pipeline!(;$INDEX; ;$doc_id; ;$field; $($branch)*),
+            inner_pipeline!(;$INDEX; ;$doc_id; ;$field; $($x)*))
+    };
+    (;$INDEX:ident; ;$doc_id:expr; ;$field:ident;
+     [$this_field:ident]) => {
+        // [field]
+        Funnel::create($doc_id, &mut $INDEX.$this_field)
+    };
     (;$INDEX:ident; ;$doc_id:expr; ;$field:ident;
      $element:ident($($param:expr),+) | [$this_field:ident] $($x:tt)*) =>
     // ;doc_id; ;field_id; Element(params) | [field]
@@ -232,3 +397,44 @@ macro_rules! pipeline {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn tee() {
+        let mut left = Collector::new();
+        let mut right = Collector::new();
+        WhitespaceTokenizer::create(Tee::create(LowercaseFilter::create(&mut left), &mut right))
+            .apply("Information Retrieval");
+        assert_eq!(left.terms(), &["information", "retrieval"]);
+        assert_eq!(right.terms(), &["Information", "Retrieval"]);
+    }
+
+    #[test]
+    fn fn_tokenizer() {
+        let mut terms = Collector::new();
+        FnTokenizer::create(|input, emit| {
+                                let mut start = 0;
+                                for (i, c) in input.char_indices() {
+                                    if c == ',' {
+                                        emit(&input[start..i]);
+                                        start = i + 1;
+                                    }
+                                }
+                                emit(&input[start..]);
+                            },
+                            &mut terms)
+            .apply("new york,berlin,");
+        assert_eq!(terms.terms(), &["new york", "berlin", ""]);
+    }
+
+    #[test]
+    fn keyword_tokenizer() {
+        let mut terms = Collector::new();
+        KeywordTokenizer::create(&mut terms).apply("New York");
+        assert_eq!(terms.terms(), &["New York"]);
+    }
+}
@@ -0,0 +1,172 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Emits all char n-grams with a length between `min` and `max` of every
+/// token it receives.
+/// "abc" with min=2, max=3 results in "ab", "abc", "bc"
+///
+/// Tokens shorter than `min` are dropped unless `keep_short_tokens` is set,
+/// in which case they are passed on as a whole.
+pub struct NGramTokenizer<TCallback> {
+    min: usize,
+    max: usize,
+    keep_short_tokens: bool,
+    callback: TCallback,
+}
+
+impl<TCallback> NGramTokenizer<TCallback> {
+    pub fn create(min: usize, max: usize, callback: TCallback) -> Self {
+        assert!(min > 0 && min <= max, "NGramTokenizer needs 0 < min <= max!");
+        NGramTokenizer {
+            min: min,
+            max: max,
+            keep_short_tokens: false,
+            callback: callback,
+        }
+    }
+
+    pub fn keep_short_tokens(mut self) -> Self {
+        self.keep_short_tokens = true;
+        self
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for NGramTokenizer<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        let boundaries = char_boundaries(input);
+        // boundaries contains the end of the input as well
+        let char_count = boundaries.len() - 1;
+        if char_count < self.min {
+            if self.keep_short_tokens && char_count > 0 {
+                self.callback.apply(input);
+            }
+            return;
+        }
+        for start in 0..char_count {
+            for length in self.min..=self.max {
+                if start + length > char_count {
+                    break;
+                }
+                self.callback.apply(&input[boundaries[start]..boundaries[start + length]]);
+            }
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for NGramTokenizer<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+/// Emits the leading char n-grams with a length between `min` and `max` of
+/// every token it receives.
+/// "search" with min=1, max=3 results in "s", "se", "sea"
+///
+/// Indexing these allows prefix matching with plain term queries.
+pub struct EdgeNGramTokenizer<TCallback> {
+    min: usize,
+    max: usize,
+    callback: TCallback,
+}
+
+impl<TCallback> EdgeNGramTokenizer<TCallback> {
+    pub fn create(min: usize, max: usize, callback: TCallback) -> Self {
+        assert!(min > 0 && min <= max, "EdgeNGramTokenizer needs 0 < min <= max!");
+        EdgeNGramTokenizer {
+            min: min,
+            max: max,
+            callback: callback,
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for EdgeNGramTokenizer<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        let boundaries = char_boundaries(input);
+        for length in self.min..=self.max {
+            if length >= boundaries.len() {
+                break;
+            }
+            self.callback.apply(&input[..boundaries[length]]);
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for EdgeNGramTokenizer<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+/// Byte offsets of every char in `input` plus `input.len()`
+fn char_boundaries(input: &str) -> Vec<usize> {
+    let mut boundaries = input.char_indices().map(|(i, _)| i).collect::<Vec<_>>();
+    boundaries.push(input.len());
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn ngrams() {
+        let mut grams = Collector::new();
+        NGramTokenizer::create(2, 3, &mut grams).apply("abc");
+        assert_eq!(grams.terms(), &["ab", "abc", "bc"]);
+    }
+
+    #[test]
+    fn multibyte_ngrams() {
+        let mut grams = Collector::new();
+        NGramTokenizer::create(2, 2, &mut grams).apply("äöü");
+        assert_eq!(grams.terms(), &["äö", "öü"]);
+    }
+
+    #[test]
+    fn short_tokens() {
+        let mut grams = Collector::new();
+        NGramTokenizer::create(3, 3, &mut grams).apply("ab");
+        assert!(grams.terms().is_empty());
+        NGramTokenizer::create(3, 3, &mut grams).keep_short_tokens().apply("ab");
+        assert_eq!(grams.terms(), &["ab"]);
+    }
+
+    #[test]
+    fn edge_ngrams() {
+        let mut grams = Collector::new();
+        EdgeNGramTokenizer::create(1, 3, &mut grams).apply("hello");
+        assert_eq!(grams.terms(), &["h", "he", "hel"]);
+    }
+
+    #[test]
+    fn short_edge_ngrams() {
+        let mut grams = Collector::new();
+        EdgeNGramTokenizer::create(2, 5, &mut grams).apply("éa");
+        assert_eq!(grams.terms(), &["éa"]);
+    }
+
+    #[test]
+    fn unbounded_max() {
+        let mut grams = Collector::new();
+        NGramTokenizer::create(2, usize::MAX, &mut grams).apply("abc");
+        assert_eq!(grams.terms(), &["ab", "abc", "bc"]);
+        let mut grams = Collector::new();
+        EdgeNGramTokenizer::create(2, usize::MAX, &mut grams).apply("abc");
+        assert_eq!(grams.terms(), &["ab", "abc"]);
+    }
+}
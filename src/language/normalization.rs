@@ -0,0 +1,96 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+use unicode_normalization::UnicodeNormalization;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Unicode normalization forms as defined in UAX #15
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum NormalizationForm {
+    NFC,
+    NFD,
+    NFKC,
+    NFKD,
+}
+
+/// Brings every token into the given `NormalizationForm`.
+/// That way precomposed characters and characters with combining marks
+/// result in the same term.
+/// Should run before lowercasing or ascii folding.
+pub struct UnicodeNormalizationFilter<TCallback> {
+    form: NormalizationForm,
+    callback: TCallback,
+}
+
+impl<TCallback> UnicodeNormalizationFilter<TCallback> {
+    pub fn create(form: NormalizationForm, callback: TCallback) -> Self {
+        UnicodeNormalizationFilter {
+            form: form,
+            callback: callback,
+        }
+    }
+
+    fn normalize(&self, input: &str) -> String {
+        match self.form {
+            NormalizationForm::NFC => input.nfc().collect(),
+            NormalizationForm::NFD => input.nfd().collect(),
+            NormalizationForm::NFKC => input.nfkc().collect(),
+            NormalizationForm::NFKD => input.nfkd().collect(),
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for UnicodeNormalizationFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        let normalized = self.normalize(input);
+        self.callback.apply(normalized);
+    }
+}
+
+impl<TCallback> CanApply<String> for UnicodeNormalizationFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        let normalized = self.normalize(&input);
+        self.callback.apply(normalized);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for UnicodeNormalizationFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn composed_and_decomposed() {
+        let mut terms = Collector::new();
+        {
+            let mut filter = UnicodeNormalizationFilter::create(NormalizationForm::NFC,
+                                                                &mut terms);
+            // Precomposed é
+            filter.apply("caf\u{e9}");
+            // e followed by a combining acute accent
+            filter.apply("cafe\u{301}");
+        }
+        assert_eq!(terms.terms()[0], terms.terms()[1]);
+    }
+
+    #[test]
+    fn compatibility() {
+        let mut terms = Collector::new();
+        UnicodeNormalizationFilter::create(NormalizationForm::NFKC, &mut terms).apply("\u{fb01}le");
+        assert_eq!(terms.terms(), &["file"]);
+    }
+}
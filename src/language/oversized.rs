@@ -0,0 +1,128 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Truncates tokens longer than `max_length` chars.
+/// Keeps the vocabulary bounded on adversarial input (base64 blobs, ...).
+/// Recall impact: two oversized tokens sharing the first `max_length` chars
+/// are indexed as the same term.
+pub struct TruncateFilter<TCallback> {
+    max_length: usize,
+    callback: TCallback,
+}
+
+impl<TCallback> TruncateFilter<TCallback> {
+    pub fn create(max_length: usize, callback: TCallback) -> Self {
+        TruncateFilter {
+            max_length: max_length,
+            callback: callback,
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for TruncateFilter<TCallback>
+    where TCallback: CanApply<&'a str>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        match input.char_indices().nth(self.max_length) {
+            Some((index, _)) => self.callback.apply(&input[..index]),
+            None => self.callback.apply(input),
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for TruncateFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+/// Replaces tokens longer than `max_length` chars by one of `buckets` terms.
+/// The bucket is chosen by hashing the token, so the vocabulary grows by at
+/// most `buckets` terms no matter how many oversized tokens are indexed.
+/// Recall impact: oversized tokens in the same bucket match each other.
+pub struct HashOversizedFilter<TCallback> {
+    max_length: usize,
+    buckets: u64,
+    callback: TCallback,
+}
+
+impl<TCallback> HashOversizedFilter<TCallback> {
+    pub fn create(max_length: usize, buckets: u64, callback: TCallback) -> Self {
+        assert!(buckets > 0, "HashOversizedFilter needs at least one bucket!");
+        HashOversizedFilter {
+            max_length: max_length,
+            buckets: buckets,
+            callback: callback,
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for HashOversizedFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        if input.chars().count() > self.max_length {
+            self.callback.apply(format!("#oversized{}", fnv1a(input) % self.buckets));
+        } else {
+            self.callback.apply(input.to_string());
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for HashOversizedFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+/// FNV-1a. Unlike the std hasher its output is guaranteed to stay the same,
+/// which matters because the resulting terms are persisted in the index.
+fn fnv1a(input: &str) -> u64 {
+    input.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn truncate() {
+        let mut terms = Collector::new();
+        {
+            let mut filter = TruncateFilter::create(3, &mut terms);
+            filter.apply("äöüß");
+            filter.apply("abc");
+            filter.apply("ab");
+        }
+        assert_eq!(terms.terms(), &["äöü", "abc", "ab"]);
+    }
+
+    #[test]
+    fn hash_oversized() {
+        let mut terms = Collector::new();
+        {
+            let mut filter = HashOversizedFilter::create(3, 4, &mut terms);
+            filter.apply("ab");
+            for i in 0..100 {
+                filter.apply(&format!("oversized{}", i));
+            }
+        }
+        assert_eq!(terms.terms()[0], "ab");
+        let mut oversized = terms.terms()[1..].to_vec();
+        assert!(oversized.iter().all(|term| term.starts_with("#oversized")));
+        oversized.sort();
+        oversized.dedup();
+        assert!(oversized.len() <= 4);
+    }
+}
@@ -0,0 +1,86 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+use regex::Regex;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Replaces every match of `pattern` in a token by `replacement`.
+/// The replacement can refer to capture groups (`$1`).
+/// Tokens that are empty after the replacement are dropped.
+/// The result is passed on borrowed, so a tokenizer can follow.
+///
+/// Panics on construction if `pattern` is not a valid regular expression.
+pub struct PatternReplaceFilter<TCallback> {
+    pattern: Regex,
+    replacement: String,
+    callback: TCallback,
+}
+
+impl<TCallback> PatternReplaceFilter<TCallback> {
+    pub fn create(pattern: &str, replacement: &str, callback: TCallback) -> Self {
+        PatternReplaceFilter {
+            pattern: Regex::new(pattern).expect("PatternReplaceFilter: Invalid pattern!"),
+            replacement: replacement.to_string(),
+            callback: callback,
+        }
+    }
+}
+
+impl<TCallback> PatternReplaceFilter<TCallback>
+    where TCallback: for<'b> CanApply<&'b str>
+{
+    fn replace(&mut self, input: &str) {
+        let replaced = self.pattern.replace_all(input, self.replacement.as_str());
+        if !replaced.is_empty() {
+            self.callback.apply(&replaced);
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for PatternReplaceFilter<TCallback>
+    where TCallback: for<'b> CanApply<&'b str>
+{
+    type Output = <TCallback as CanApply<&'a str>>::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.replace(input);
+    }
+}
+
+impl<TCallback> CanApply<String> for PatternReplaceFilter<TCallback>
+    where TCallback: for<'b> CanApply<&'b str>
+{
+    type Output = <TCallback as CanApply<&'static str>>::Output;
+    fn apply(&mut self, input: String) {
+        self.replace(&input);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for PatternReplaceFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::WhitespaceTokenizer;
+    use test_utils::Collector;
+
+    #[test]
+    fn strip_possessive() {
+        let mut terms = Collector::new();
+        WhitespaceTokenizer::create(PatternReplaceFilter::create("'s$", "", &mut terms))
+            .apply("perlin's index's 's");
+        assert_eq!(terms.terms(), &["perlin", "index"]);
+    }
+
+    #[test]
+    fn collapse_hyphens() {
+        let mut terms = Collector::new();
+        PatternReplaceFilter::create("(\\w)-(\\w)", "$1$2", &mut terms).apply("e-mail");
+        assert_eq!(terms.terms(), &["email"]);
+    }
+}
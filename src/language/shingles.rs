@@ -0,0 +1,131 @@
+use std::cmp;
+use std::collections::VecDeque;
+
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Joins adjacent tokens into shingles.
+/// "information retrieval systems" with min=2, max=2 and separator "_"
+/// results in "information_retrieval", "retrieval_systems".
+///
+/// The pipeline has no end of stream signal. So instead of flushing at the
+/// end, all shingles ending in a token are emitted as soon as it arrives.
+/// Only the last `max` tokens are kept.
+pub struct ShingleFilter<TCallback> {
+    min: usize,
+    max: usize,
+    separator: String,
+    unigrams: bool,
+    window: VecDeque<String>,
+    callback: TCallback,
+}
+
+const MAX_INITIAL_WINDOW: usize = 8;
+
+impl<TCallback> ShingleFilter<TCallback> {
+    pub fn create(min: usize, max: usize, separator: &str, callback: TCallback) -> Self {
+        assert!(min > 1 && min <= max, "ShingleFilter needs 1 < min <= max!");
+        ShingleFilter {
+            min: min,
+            max: max,
+            separator: separator.to_string(),
+            unigrams: false,
+            // max may be usize::MAX, the window grows as needed beyond that
+            window: VecDeque::with_capacity(cmp::min(max, MAX_INITIAL_WINDOW)),
+            callback: callback,
+        }
+    }
+
+    /// Passes on the single tokens as well
+    pub fn with_unigrams(mut self) -> Self {
+        self.unigrams = true;
+        self
+    }
+}
+
+impl<TCallback> ShingleFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    fn push(&mut self, token: String) {
+        if self.unigrams {
+            self.callback.apply(token.clone());
+        }
+        if self.window.len() == self.max {
+            self.window.pop_front();
+        }
+        self.window.push_back(token);
+        for size in self.min..=self.max {
+            if size > self.window.len() {
+                break;
+            }
+            let start = self.window.len() - size;
+            let shingle = self.window
+                .iter()
+                .skip(start)
+                .map(|token| token.as_str())
+                .collect::<Vec<_>>()
+                .join(&self.separator);
+            self.callback.apply(shingle);
+        }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for ShingleFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.push(input.to_string());
+    }
+}
+
+impl<TCallback> CanApply<String> for ShingleFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        self.push(input);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for ShingleFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::WhitespaceTokenizer;
+    use test_utils::Collector;
+
+    #[test]
+    fn two_shingles() {
+        let mut terms = Collector::new();
+        WhitespaceTokenizer::create(ShingleFilter::create(2, 2, "_", &mut terms))
+            .apply("information retrieval systems");
+        assert_eq!(terms.terms(),
+                   &["information_retrieval", "retrieval_systems"]);
+    }
+
+    #[test]
+    fn shingles_with_unigrams() {
+        let mut terms = Collector::new();
+        WhitespaceTokenizer::create(ShingleFilter::create(2, 3, " ", &mut terms).with_unigrams())
+            .apply("a b c");
+        assert_eq!(terms.terms(), &["a", "b", "a b", "c", "b c", "a b c"]);
+    }
+
+    #[test]
+    fn unbounded_max() {
+        let mut terms = Collector::new();
+        WhitespaceTokenizer::create(ShingleFilter::create(2, usize::MAX, " ", &mut terms))
+            .apply("a b c");
+        assert_eq!(terms.terms(), &["a b", "b c", "a b c"]);
+    }
+}
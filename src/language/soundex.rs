@@ -0,0 +1,124 @@
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Operand, ToOperands};
+
+/// Replaces every token by its (american) Soundex code.
+/// Names that sound alike like "Robert" and "Rupert" result in the same code
+/// "R163".
+///
+/// Only ASCII letters are considered. Tokens without any are passed on
+/// unchanged.
+pub struct SoundexFilter<TCallback> {
+    callback: TCallback,
+}
+
+impl<TCallback> SoundexFilter<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        SoundexFilter { callback: callback }
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for SoundexFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        match soundex(input) {
+            Some(code) => self.callback.apply(code),
+            None => self.callback.apply(input.to_string()),
+        }
+    }
+}
+
+impl<TCallback> CanApply<String> for SoundexFilter<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        match soundex(&input) {
+            Some(code) => self.callback.apply(code),
+            None => self.callback.apply(input),
+        }
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for SoundexFilter<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+fn soundex(input: &str) -> Option<String> {
+    let mut letters = input.chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase());
+    let first = letters.next()?;
+    let mut code = String::with_capacity(4);
+    code.push(first);
+    let mut last_digit = soundex_digit(first);
+    for letter in letters {
+        if code.len() == 4 {
+            break;
+        }
+        match soundex_digit(letter) {
+            Some(digit) => {
+                if last_digit != Some(digit) {
+                    code.push(digit);
+                }
+                last_digit = Some(digit);
+            }
+            // H and W do not separate letters with the same code
+            None if letter == 'H' || letter == 'W' => {}
+            // Vowels do
+            None => last_digit = None,
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    Some(code)
+}
+
+fn soundex_digit(letter: char) -> Option<char> {
+    match letter {
+        'B' | 'F' | 'P' | 'V' => Some('1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some('2'),
+        'D' | 'T' => Some('3'),
+        'L' => Some('4'),
+        'M' | 'N' => Some('5'),
+        'R' => Some('6'),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn codes() {
+        assert_eq!(soundex("Robert"), Some("R163".to_string()));
+        assert_eq!(soundex("Rupert"), Some("R163".to_string()));
+        assert_eq!(soundex("Ashcraft"), Some("A261".to_string()));
+        assert_eq!(soundex("Tymczak"), Some("T522".to_string()));
+        assert_eq!(soundex("Pfister"), Some("P236".to_string()));
+        assert_eq!(soundex("Lee"), Some("L000".to_string()));
+        assert_eq!(soundex("42"), None);
+    }
+
+    #[test]
+    fn names_collide() {
+        let mut terms = Collector::new();
+        {
+            let mut filter = SoundexFilter::create(&mut terms);
+            filter.apply("Smith");
+            filter.apply("Smyth");
+            filter.apply("1984");
+        }
+        assert_eq!(terms.terms(), &["S530", "S530", "1984"]);
+    }
+}
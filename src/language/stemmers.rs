@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 use perlin_core::utils::seeking_iterator::PeekableSeekable;
 
 
@@ -45,3 +47,161 @@ impl<TCallback> Stemmer<TCallback> {
         }
     }
 }
+
+/// English stemmer using the Porter2 algorithm.
+/// Shorthand for `Stemmer(Algorithm::English)`
+pub struct PorterStemmer<TCallback>(Stemmer<TCallback>);
+
+impl<TCallback> PorterStemmer<TCallback> {
+    pub fn create(callback: TCallback) -> Self {
+        PorterStemmer(Stemmer::create(Algorithm::English, callback))
+    }
+}
+
+impl<TCallback> CanApply<String> for PorterStemmer<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        self.0.apply(input);
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for PorterStemmer<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.0.apply(input);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for PorterStemmer<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.0.to_operands()
+    }
+}
+
+/// Languages supported by `SnowballStemmer`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Language {
+    Dutch,
+    English,
+    French,
+    German,
+    Italian,
+    Portuguese,
+    Spanish,
+}
+
+impl Language {
+    fn algorithm(self) -> Algorithm {
+        match self {
+            Language::Dutch => Algorithm::Dutch,
+            Language::English => Algorithm::English,
+            Language::French => Algorithm::French,
+            Language::German => Algorithm::German,
+            Language::Italian => Algorithm::Italian,
+            Language::Portuguese => Algorithm::Portuguese,
+            Language::Spanish => Algorithm::Spanish,
+        }
+    }
+}
+
+/// Parses ISO 639-1 codes like "de".
+/// Fails for languages that have no stemmer.
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        match code {
+            "nl" => Ok(Language::Dutch),
+            "en" => Ok(Language::English),
+            "fr" => Ok(Language::French),
+            "de" => Ok(Language::German),
+            "it" => Ok(Language::Italian),
+            "pt" => Ok(Language::Portuguese),
+            "es" => Ok(Language::Spanish),
+            _ => Err(format!("No stemmer available for language {:?}", code)),
+        }
+    }
+}
+
+/// Snowball stemmer for the given `Language`
+pub struct SnowballStemmer<TCallback>(Stemmer<TCallback>);
+
+impl<TCallback> SnowballStemmer<TCallback> {
+    pub fn create(language: Language, callback: TCallback) -> Self {
+        SnowballStemmer(Stemmer::create(language.algorithm(), callback))
+    }
+}
+
+impl<TCallback> CanApply<String> for SnowballStemmer<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: String) {
+        self.0.apply(input);
+    }
+}
+
+impl<'a, TCallback> CanApply<&'a str> for SnowballStemmer<TCallback>
+    where TCallback: CanApply<String>
+{
+    type Output = TCallback::Output;
+    fn apply(&mut self, input: &'a str) {
+        self.0.apply(input);
+    }
+}
+
+impl<'a, TCallback> ToOperands<'a> for SnowballStemmer<TCallback>
+    where TCallback: ToOperands<'a>
+{
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.0.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_utils::Collector;
+
+    #[test]
+    fn porter_stems() {
+        let words = [("running", "run"),
+                     ("runs", "run"),
+                     ("ponies", "poni"),
+                     ("caresses", "caress"),
+                     ("generously", "generous"),
+                     ("run", "run"),
+                     ("a", "a")];
+        for &(word, stem) in &words {
+            let mut stems = Collector::new();
+            PorterStemmer::create(&mut stems).apply(word);
+            assert_eq!(stems.terms(), &[stem]);
+        }
+    }
+
+    #[test]
+    fn snowball_stems() {
+        let words = [(Language::German, "häuser", "haus"),
+                     (Language::German, "katzen", "katz"),
+                     (Language::French, "chevaux", "cheval"),
+                     (Language::Spanish, "chicas", "chic"),
+                     (Language::English, "ponies", "poni")];
+        for &(language, word, stem) in &words {
+            let mut stems = Collector::new();
+            SnowballStemmer::create(language, &mut stems).apply(word);
+            assert_eq!(stems.terms(), &[stem]);
+        }
+    }
+
+    #[test]
+    fn language_codes() {
+        assert_eq!("de".parse::<Language>(), Ok(Language::German));
+        assert!("xx".parse::<Language>().is_err());
+    }
+}
@@ -1,27 +1,118 @@
+use std::io::{self, Read, BufRead, BufReader};
+
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
 use language::CanApply;
+use query::{Operand, ToOperands};
 
-/// Proof of concept stopword filter!
+/// Drops every token that is contained in the stopword list.
+/// Dropped tokens never reach a funnel, so at query time they do not
+/// result in an operand either.
 pub struct StopwordFilter<CB> {
     stopwords: Vec<String>,
+    case_sensitive: bool,
     callback: CB
 }
 
 impl<CB> StopwordFilter<CB> {
-    pub fn create(stopwords: Vec<String>, callback: CB) -> Self{
+    pub fn create(mut stopwords: Vec<String>, callback: CB) -> Self{
+        stopwords.sort();
+        stopwords.dedup();
         StopwordFilter{
             stopwords: stopwords,
+            case_sensitive: true,
             callback: callback
         }
     }
+
+    pub fn from_words<I: IntoIterator<Item = String>>(words: I, callback: CB) -> Self {
+        Self::create(words.into_iter().collect(), callback)
+    }
+
+    /// Reads one stopword per line. Empty lines are ignored.
+    pub fn from_reader<R: Read>(reader: R, callback: CB) -> io::Result<Self> {
+        let mut words = Vec::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let word = line.trim();
+            if !word.is_empty() {
+                words.push(word.to_string());
+            }
+        }
+        Ok(Self::create(words, callback))
+    }
+
+    /// Matches tokens against the stopwords regardless of their case
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_sensitive = false;
+        let stopwords = self.stopwords.iter().map(|word| word.to_lowercase()).collect();
+        self.stopwords = stopwords;
+        self.stopwords.sort();
+        self.stopwords.dedup();
+        self
+    }
+
+    fn is_stopword(&self, input: &str) -> bool {
+        if self.case_sensitive {
+            self.stopwords.binary_search_by(|word| word.as_str().cmp(input)).is_ok()
+        } else {
+            self.stopwords.binary_search(&input.to_lowercase()).is_ok()
+        }
+    }
 }
 
 
 impl<CB: CanApply<String>> CanApply<String> for StopwordFilter<CB> {
     type Output = CB::Output;
-    
+
     fn apply(&mut self, input: String) {
-        if self.stopwords.binary_search(&input).is_err() {
+        if !self.is_stopword(&input) {
             self.callback.apply(input);
         }
     }
 }
+
+impl<'a, CB: CanApply<&'a str>> CanApply<&'a str> for StopwordFilter<CB> {
+    type Output = CB::Output;
+
+    fn apply(&mut self, input: &'a str) {
+        if !self.is_stopword(input) {
+            self.callback.apply(input);
+        }
+    }
+}
+
+impl<'a, CB: ToOperands<'a>> ToOperands<'a> for StopwordFilter<CB> {
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.callback.to_operands()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language::WhitespaceTokenizer;
+    use test_utils::Collector;
+
+    #[test]
+    fn filters_stopwords() {
+        let mut terms = Collector::new();
+        {
+            let stopwords = StopwordFilter::from_words(vec!["the".to_string()], &mut terms);
+            WhitespaceTokenizer::create(stopwords).apply("the quick fox");
+        }
+        assert_eq!(terms.terms(), &["quick", "fox"]);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let mut terms = Collector::new();
+        {
+            let stopwords = StopwordFilter::from_reader("The\n\nA\n".as_bytes(), &mut terms)
+                .unwrap()
+                .case_insensitive();
+            WhitespaceTokenizer::create(stopwords).apply("THE quick a fox");
+        }
+        assert_eq!(terms.terms(), &["quick", "fox"]);
+    }
+}
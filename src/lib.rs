@@ -12,6 +12,12 @@
 extern crate perlin_derive;
 extern crate perlin_core;
 extern crate rust_stemmers;
+extern crate unicode_normalization;
+extern crate regex;
+#[cfg(feature = "json")]
+extern crate serde;
+#[cfg(feature = "json")]
+extern crate serde_json;
 
 #[macro_use]
 mod utils;
@@ -21,6 +27,8 @@ pub mod language;
 pub mod query;
 pub mod document_index;
 pub mod field;
+#[cfg(feature = "json")]
+pub mod json;
 
 #[cfg(test)]
 pub mod test_utils;
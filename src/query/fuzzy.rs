@@ -0,0 +1,155 @@
+use std::hash::Hash;
+use std::cmp::{min, max};
+
+use perlin_core::index::posting::PostingIterator;
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Weight, ToOperands, Operand};
+use field::Field;
+
+/// Like `Funnel`, but instead of querying the term itself it queries every
+/// term of the fields vocabulary that is at most `max_distance` edits away.
+/// That way "retreival" still finds "retrieval".
+///
+/// All matching terms of one query term are combined into a single operand
+/// that matches if any of them does. At most `max_expansions` terms are
+/// used, the closest ones first. Every edit lowers the weight of a term, so
+/// the exact term weighs more than a rare misspelling.
+///
+/// This iterates the whole vocabulary for every query term.
+///
+/// In `query_pipeline!` it ends a pipeline as `[Fuzzy(max_distance) in field]`
+/// or `[Fuzzy(max_distance, max_expansions) in field]`.
+pub struct FuzzyFunnel<'a, T: 'a + Hash + Eq> {
+    index: &'a Field<T>,
+    max_distance: usize,
+    max_expansions: usize,
+    result: Vec<PeekableSeekable<Operand<'a>>>,
+}
+
+/// Default for the maximum number of terms a fuzzy term expands to
+pub const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+impl<'a, T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static> FuzzyFunnel<'a, T> {
+    pub fn create(max_distance: usize, index: &'a Field<T>) -> Self {
+        FuzzyFunnel {
+            index,
+            max_distance,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            result: Vec::new(),
+        }
+    }
+
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    fn query(&mut self, term: &str) {
+        let index = self.index;
+        let w = 1. / index.term_doc_ratio;
+        let term_length = term.chars().count();
+        let mut candidates = Vec::new();
+        for (candidate, _) in index.iterate_terms() {
+            let candidate_length = candidate.as_ref().chars().count();
+            // Cheap pre-filter: Every char of length difference is one edit
+            if max(term_length, candidate_length) - min(term_length, candidate_length) >
+               self.max_distance {
+                continue;
+            }
+            if let Some(distance) = bounded_levenshtein(term, candidate.as_ref(), self.max_distance) {
+                candidates.push((distance, candidate));
+            }
+        }
+        candidates.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+        candidates.truncate(self.max_expansions);
+
+        let mut weight = Weight(0.);
+        let mut operands = Vec::new();
+        for (distance, candidate) in candidates {
+            if let (idf, PostingIterator::Decoder(decoder)) = index.query_atom(candidate) {
+                let candidate_weight = Weight(idf.0 * w / (distance + 1) as f32);
+                if candidate_weight.0 > 0. {
+                    weight = max(weight, candidate_weight);
+                    operands.push(PeekableSeekable::new(Operand::Term(candidate_weight,
+                                                                      decoder,
+                                                                      candidate.to_string(),
+                                                                      index.name.clone())));
+                }
+            }
+        }
+        if !operands.is_empty() {
+            self.result.push(PeekableSeekable::new(Operand::any(weight, operands)));
+        }
+    }
+}
+
+impl<'a, 'b, T> CanApply<&'b str> for FuzzyFunnel<'a, T>
+    where T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static
+{
+    type Output = T;
+
+    fn apply(&mut self, term: &'b str) {
+        self.query(term);
+    }
+}
+
+impl<'a, T> CanApply<String> for FuzzyFunnel<'a, T>
+    where T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static
+{
+    type Output = T;
+
+    fn apply(&mut self, term: String) {
+        self.query(&term);
+    }
+}
+
+impl<'a, T: 'a + Hash + Eq> ToOperands<'a> for FuzzyFunnel<'a, T> {
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.result
+    }
+}
+
+/// Levenshtein distance between `a` and `b` on chars.
+/// Returns `None` as soon as the distance is known to exceed `max_distance`.
+fn bounded_levenshtein(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..b.len() + 1).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = min(substitution, min(previous[j + 1], current[j]) + 1);
+            row_min = min(row_min, current[j + 1]);
+        }
+        // The distance can only grow from here on
+        if row_min > max_distance {
+            return None;
+        }
+        ::std::mem::swap(&mut previous, &mut current);
+    }
+    let distance = previous[b.len()];
+    if distance > max_distance {
+        None
+    } else {
+        Some(distance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::bounded_levenshtein;
+
+    #[test]
+    fn levenshtein() {
+        assert_eq!(bounded_levenshtein("retrieval", "retrieval", 2), Some(0));
+        assert_eq!(bounded_levenshtein("retreival", "retrieval", 2), Some(2));
+        assert_eq!(bounded_levenshtein("kitten", "sitting", 3), Some(3));
+        assert_eq!(bounded_levenshtein("kitten", "sitting", 2), None);
+        assert_eq!(bounded_levenshtein("", "abc", 3), Some(3));
+        assert_eq!(bounded_levenshtein("über", "uber", 1), Some(1));
+    }
+}
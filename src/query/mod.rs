@@ -1,15 +1,23 @@
 use std::cmp::Ordering;
 use std::fmt;
+use std::hash::Hash;
+use std::rc::Rc;
 
-use perlin_core::index::posting::{Posting, PostingIterator, PostingDecoder};
+use perlin_core::index::posting::{Posting, PostingIterator, PostingDecoder, DocId};
 use perlin_core::utils::seeking_iterator::{PeekableSeekable, SeekingIterator};
 use perlin_core::utils::progress::Progress;
 
+use field::{Field, FieldSupplement};
+
 pub use query::operators::{And, Funnel, Combinator};
+pub use query::fuzzy::FuzzyFunnel;
+pub use query::prefix::PrefixFunnel;
 
 #[macro_use]
 pub mod query_pipeline;
 mod operators;
+mod fuzzy;
+mod prefix;
 
 #[derive(Debug, Copy, Clone)]
 pub enum ChainingOperator {
@@ -33,8 +41,10 @@ pub struct WeightingOperator<'a> {
     max_weight: Weight,
     already_emitted: Vec<Posting>,
     filters: Vec<PeekableSeekable<Operand<'a>>>,
+    must_not: Vec<PeekableSeekable<Operand<'a>>>,
     operands: Vec<PeekableSeekable<Operand<'a>>>,
     current_operands: Option<Vec<PeekableSeekable<Operand<'a>>>>,
+    current_must_not: Vec<PeekableSeekable<Operand<'a>>>,
     counter: usize,
 }
 
@@ -50,6 +60,10 @@ impl<'a> Iterator for WeightingOperator<'a> {
 
             let posting = next.unwrap();
 
+            if self.is_excluded(&posting) {
+                continue;
+            }
+
             let index = match self.already_emitted.binary_search(&posting) {
                 Ok(_) => {
                     // We already emitted that posting
@@ -128,6 +142,8 @@ impl<'a> WeightingOperator<'a> {
                         // TODO: Sort current operands by length(!)
                         self.counter += 1;
                         self.current_operands = Some(new_current_operands);
+                        // Every step starts from the first document again
+                        self.current_must_not = self.must_not.clone();
                         continue;
                     } else {
                         // We are done!
@@ -144,6 +160,15 @@ impl<'a> WeightingOperator<'a> {
         }
     }
 
+    /// Checks if any of the must not operands contains `posting`.
+    /// Postings are increasing within one step, so the operands only need to
+    /// be seeked forward.
+    fn is_excluded(&mut self, posting: &Posting) -> bool {
+        self.current_must_not
+            .iter_mut()
+            .any(|op| op.peek_seek(posting).map_or(false, |p| *p == *posting))
+    }
+
     // TODO: Think about something more correct(!)
     pub fn progress(&self) -> Progress {
         if let Some(ref operands) = self.current_operands {
@@ -156,8 +181,11 @@ impl<'a> WeightingOperator<'a> {
         }
     }
 
+    /// `operands` are weighted and optional, `filters` must match and
+    /// `must_not` must not match.
     pub fn create(mut operands: Vec<PeekableSeekable<Operand<'a>>>,
-                  filters: Vec<PeekableSeekable<Operand<'a>>>)
+                  filters: Vec<PeekableSeekable<Operand<'a>>>,
+                  must_not: Vec<PeekableSeekable<Operand<'a>>>)
                   -> Self {
         operands.sort_by_key(|op| op.inner().weight());
         let mut current_operands = operands.clone();
@@ -167,6 +195,8 @@ impl<'a> WeightingOperator<'a> {
             already_emitted: Vec::new(),
             max_weight,
             filters,
+            current_must_not: must_not.clone(),
+            must_not,
             operands,
             current_operands: Some(current_operands),
             // Step 0 is set up in the lines before
@@ -179,6 +209,12 @@ impl<'a> WeightingOperator<'a> {
 #[derive(Clone)]
 pub enum Operand<'a> {
     Term(Weight, PostingDecoder<'a>, String, String),
+    /// Matches every document. Holds the next `DocId` to yield and the
+    /// number of documents
+    All(Weight, u32, u32),
+    /// Matches every document that one of the operands matches.
+    /// Holds the next `DocId` to yield
+    Or(Weight, u32, Vec<PeekableSeekable<Operand<'a>>>),
 }
 
 impl<'a> fmt::Debug for Operand<'a> {
@@ -191,6 +227,18 @@ impl<'a> fmt::Debug for Operand<'a> {
                        field,
                        weight)
             }
+            Operand::All(weight, _, doc_count) => {
+                write!(f,
+                       "Matching all {:?} documents with weight {:?}",
+                       doc_count,
+                       weight)
+            }
+            Operand::Or(weight, _, ref operands) => {
+                write!(f,
+                       "Matching any of {:?} with weight {:?}",
+                       operands.iter().map(|op| op.inner()).collect::<Vec<_>>(),
+                       weight)
+            }
         }
     }
 }
@@ -201,6 +249,23 @@ impl<'a> Iterator for Operand<'a> {
     fn next(&mut self) -> Option<Posting> {
         match *self {
             Operand::Term(_, ref mut decoder, _, _) => decoder.next(),
+            Operand::All(_, ref mut next, doc_count) => {
+                if *next < doc_count {
+                    *next += 1;
+                    Some(Posting(DocId(*next - 1)))
+                } else {
+                    None
+                }
+            }
+            Operand::Or(_, ref mut next, ref mut operands) => {
+                // Smallest posting of all operands that was not yielded yet
+                let target = Posting(DocId(*next));
+                let posting = operands.iter_mut()
+                    .filter_map(|op| op.peek_seek(&target).cloned())
+                    .min()?;
+                *next = (posting.0).0 + 1;
+                Some(posting)
+            }
         }
     }
 }
@@ -211,20 +276,53 @@ impl<'a> SeekingIterator for Operand<'a> {
     fn next_seek(&mut self, other: &Posting) -> Option<Posting> {
         match *self {
             Operand::Term(_, ref mut decoder, _, _) => decoder.next_seek(other),
+            Operand::All(_, ref mut next, _) |
+            Operand::Or(_, ref mut next, _) => {
+                if *next < (other.0).0 {
+                    *next = (other.0).0;
+                }
+                Iterator::next(self)
+            }
         }
     }
 }
 
 impl<'a> Operand<'a> {
+    /// Creates an operand that yields every `DocId` in `0..doc_count`
+    pub fn match_all(doc_count: u32) -> Self {
+        Operand::All(Weight(1.0), 0, doc_count)
+    }
+
+    /// Creates an operand that yields every `DocId` any of `operands` yields
+    pub fn any(weight: Weight, operands: Vec<PeekableSeekable<Operand<'a>>>) -> Self {
+        Operand::Or(weight, 0, operands)
+    }
+
     pub fn weight(&self) -> Weight {
         match *self {
-            Operand::Term(w, _, _, _) => w,
+            Operand::Term(w, _, _, _) |
+            Operand::All(w, _, _) |
+            Operand::Or(w, _, _) => w,
         }
     }
 
     pub fn progress(&self) -> Progress {
         match *self {
             Operand::Term(_, ref decoder, _, _) => decoder.progress(),
+            Operand::All(_, next, doc_count) => {
+                if next >= doc_count {
+                    Progress::done()
+                } else {
+                    Progress::from(next as f32 / doc_count as f32)
+                }
+            }
+            Operand::Or(_, _, ref operands) => {
+                // The operand that is furthest behind decides
+                operands.iter()
+                    .map(|op| op.inner().progress())
+                    .min()
+                    .unwrap_or(Progress::done())
+            }
         }
     }
 }
@@ -233,10 +331,20 @@ pub trait ToOperands<'a> {
     fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>>;
 }
 
+/// Checked against every candidate `DocId` after the inverted index matched it
+pub type DocFilter<'a> = Rc<Fn(DocId) -> bool + 'a>;
+
+/// A query consists of the query string which is run through the query
+/// pipeline and additional clauses:
+/// `filter` must match, `should` boosts matching documents but is not
+/// required and `must_not` excludes documents.
 #[derive(Clone)]
 pub struct Query<'a> {
     pub query: &'a str,
     pub filter: Vec<PeekableSeekable<Operand<'a>>>,
+    pub should: Vec<PeekableSeekable<Operand<'a>>>,
+    pub must_not: Vec<PeekableSeekable<Operand<'a>>>,
+    pub doc_filter: Vec<DocFilter<'a>>,
 }
 
 impl<'a> Query<'a> {
@@ -244,16 +352,67 @@ impl<'a> Query<'a> {
         Query {
             query: query,
             filter: vec![],
+            should: vec![],
+            must_not: vec![],
+            doc_filter: vec![],
         }
     }
 
-    pub fn filter_by(mut self, filter: PostingIterator<'a>) -> Self {
+    /// Only yields documents whose doc value in `field` lies within `[low, high]`.
+    /// Instead of expanding the range into terms and intersecting their
+    /// postings, every candidate of the boolean part is looked up in the
+    /// doc values. This is cheap as long as the boolean part is selective.
+    ///
+    /// Panics if `field` was not created with doc values.
+    pub fn filter_range<T>(mut self, field: &'a Field<T>, low: T, high: T) -> Self
+        where T: Hash + Eq + Ord + Clone + 'a
+    {
+        let doc_values = if let FieldSupplement::DocValues(ref doc_values) = field.supplement {
+            doc_values
+        } else {
+            panic!("Field {} has no doc values!", field.name);
+        };
+        self.doc_filter.push(Rc::new(move |doc_id| match doc_values.get(doc_id) {
+            Some(value) => *value >= low && *value <= high,
+            None => false,
+        }));
+        self
+    }
+
+    /// Matches every document of an index with `doc_count` documents.
+    /// Combined with other filters and an empty query string this allows
+    /// browsing the index without any search terms.
+    ///
+    /// Prefer the generated `match_all` of the index, which knows the
+    /// document count.
+    pub fn match_all(mut self, doc_count: u32) -> Self {
+        self.filter.push(PeekableSeekable::new(Operand::match_all(doc_count)));
+        self
+    }
+
+    /// Adds a clause to the query.
+    /// `Must` clauses are required, `May` clauses boost documents they match
+    /// and `MustNot` clauses exclude documents.
+    ///
+    /// `May` clauses are weighted like the terms of the query string, but
+    /// with a fixed `Weight(1.0)` instead of an idf based weight. So how much
+    /// they boost depends on the idf of the query terms.
+    /// Like any other weighted operand a `May` clause is optional. Unless a
+    /// `Must` clause restricts the results, documents matching only the
+    /// `May` clause are yielded as well, after the documents that match
+    /// both.
+    pub fn filter_by(mut self, operator: ChainingOperator, filter: PostingIterator<'a>) -> Self {
         match filter {
             PostingIterator::Decoder(decoder) => {
-                self.filter.push(PeekableSeekable::new(Operand::Term(Weight(1.0),
-                                                                     decoder,
-                                                                     "filter term".to_string(),
-                                                                     "filter field".to_string())))
+                let operand = PeekableSeekable::new(Operand::Term(Weight(1.0),
+                                                                  decoder,
+                                                                  "filter term".to_string(),
+                                                                  "filter field".to_string()));
+                match operator {
+                    ChainingOperator::Must => self.filter.push(operand),
+                    ChainingOperator::May => self.should.push(operand),
+                    ChainingOperator::MustNot => self.must_not.push(operand),
+                }
             }
             _ => {}
         }
@@ -0,0 +1,98 @@
+use std::hash::Hash;
+use std::cmp::max;
+
+use perlin_core::index::posting::PostingIterator;
+use perlin_core::utils::seeking_iterator::PeekableSeekable;
+
+use language::CanApply;
+use query::{Weight, ToOperands, Operand};
+use field::Field;
+
+/// Like `Funnel`, but instead of querying the term itself it queries every
+/// term of the fields vocabulary that starts with it. That way "inform" finds
+/// "information" and "informal".
+///
+/// All matching terms of one query term are combined into a single operand
+/// that matches if any of them does. At most `max_expansions` terms are
+/// used, the most frequent ones first. If no term matches, the operand
+/// matches nothing.
+///
+/// This iterates the whole vocabulary for every query term.
+///
+/// In `query_pipeline!` it ends a pipeline as `[Prefix in field]`
+/// or `[Prefix(max_expansions) in field]`.
+pub struct PrefixFunnel<'a, T: 'a + Hash + Eq> {
+    index: &'a Field<T>,
+    max_expansions: usize,
+    result: Vec<PeekableSeekable<Operand<'a>>>,
+}
+
+/// Default for the maximum number of terms a prefix expands to
+pub const DEFAULT_MAX_EXPANSIONS: usize = 50;
+
+impl<'a, T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static> PrefixFunnel<'a, T> {
+    pub fn create(index: &'a Field<T>) -> Self {
+        PrefixFunnel {
+            index,
+            max_expansions: DEFAULT_MAX_EXPANSIONS,
+            result: Vec::new(),
+        }
+    }
+
+    pub fn with_max_expansions(mut self, max_expansions: usize) -> Self {
+        self.max_expansions = max_expansions;
+        self
+    }
+
+    fn query(&mut self, prefix: &str) {
+        let index = self.index;
+        let w = 1. / index.term_doc_ratio;
+        let mut candidates = index.iterate_terms()
+            .filter(|&(term, _)| term.as_ref().starts_with(prefix))
+            .map(|(term, term_id)| (index.term_df(term_id), term))
+            .collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        candidates.truncate(self.max_expansions);
+
+        let mut weight = Weight(0.);
+        let mut operands = Vec::new();
+        for (_, candidate) in candidates {
+            if let (idf, PostingIterator::Decoder(decoder)) = index.query_atom(candidate) {
+                let candidate_weight = Weight(idf.0 * w);
+                weight = max(weight, candidate_weight);
+                operands.push(PeekableSeekable::new(Operand::Term(candidate_weight,
+                                                                  decoder,
+                                                                  candidate.to_string(),
+                                                                  index.name.clone())));
+            }
+        }
+        // Pushed even without operands, so an unknown prefix yields nothing
+        self.result.push(PeekableSeekable::new(Operand::any(weight, operands)));
+    }
+}
+
+impl<'a, 'b, T> CanApply<&'b str> for PrefixFunnel<'a, T>
+    where T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static
+{
+    type Output = T;
+
+    fn apply(&mut self, prefix: &'b str) {
+        self.query(prefix);
+    }
+}
+
+impl<'a, T> CanApply<String> for PrefixFunnel<'a, T>
+    where T: 'a + Hash + Eq + Ord + Clone + AsRef<str> + ToString + 'static
+{
+    type Output = T;
+
+    fn apply(&mut self, prefix: String) {
+        self.query(&prefix);
+    }
+}
+
+impl<'a, T: 'a + Hash + Eq> ToOperands<'a> for PrefixFunnel<'a, T> {
+    fn to_operands(self) -> Vec<PeekableSeekable<Operand<'a>>> {
+        self.result
+    }
+}
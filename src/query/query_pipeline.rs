@@ -11,6 +11,14 @@ macro_rules! inner_query_pipe {
         // >
         inner_query_pipe!(;$INDEX; $($x)*)
     };
+    (;$INDEX:ident;
+     Tee { $($branch:tt)* } $($x:tt)*) =>
+    // Tee { Elements > [OP in other_field] }
+    // Unlike in pipeline! the branch must end in a field
+    {
+        $crate::language::Tee::create(inner_query_pipe!(;$INDEX; $($branch)*),
+                                      inner_query_pipe!(;$INDEX; $($x)*))
+    };
     (;$INDEX:ident;
      $element:ident($($param:expr),+)
      | [$operator:ident in $this_field:ident] $($x:tt)*) =>
@@ -35,6 +43,28 @@ macro_rules! inner_query_pipe {
             operand!(;$INDEX; [$operator in $this_field]),
             inner_query_pipe!(;$INDEX; $($x)*))
     };
+    (;$INDEX:ident;
+     [Fuzzy($distance:expr, $max_expansions:expr) in $this_field:ident]) => {
+        // [Fuzzy(max_distance, max_expansions) in field]
+        $crate::query::FuzzyFunnel::create($distance, &$INDEX.$this_field)
+            .with_max_expansions($max_expansions)
+    };
+    (;$INDEX:ident;
+     [Fuzzy($distance:expr) in $this_field:ident]) => {
+        // [Fuzzy(max_distance) in field]
+        $crate::query::FuzzyFunnel::create($distance, &$INDEX.$this_field)
+    };
+    (;$INDEX:ident;
+     [Prefix($max_expansions:expr) in $this_field:ident]) => {
+        // [Prefix(max_expansions) in field]
+        $crate::query::PrefixFunnel::create(&$INDEX.$this_field)
+            .with_max_expansions($max_expansions)
+    };
+    (;$INDEX:ident;
+     [Prefix in $this_field:ident]) => {
+        // [Prefix in field]
+        $crate::query::PrefixFunnel::create(&$INDEX.$this_field)
+    };
     (;$INDEX:ident;
      [$operator:ident in $this_field:ident]) => {
         // [All in field]
@@ -60,7 +90,7 @@ macro_rules! query_pipeline {
             // Build the pipeline
             let mut pipeline = inner_query_pipe!(;index; $($x)*);
             // Run the query-string through it
-            pipeline.apply(&query.query);
+            pipeline.apply(query.query);
             // And retrieve all operands
             pipeline.to_operands()
         })
@@ -2,6 +2,8 @@ use std::fs;
 use std::env::temp_dir;
 use std::path::PathBuf;
 
+use language::CanApply;
+
 const TEST_FOLDER: &'static str = "perlin_tests";
 
 /// Returns the `Path` to a folder where tests can do their worst
@@ -14,3 +16,37 @@ pub fn create_test_dir(dir: &str) -> PathBuf {
     fs::create_dir_all(&path).unwrap();
     path
 }
+
+/// Pipeline element that collects everything it is applied to
+pub struct Collector(Vec<String>);
+
+impl Collector {
+    pub fn new() -> Self {
+        Collector(Vec::new())
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl<'a, 'b> CanApply<&'a str> for &'b mut Collector {
+    type Output = String;
+    fn apply(&mut self, input: &'a str) {
+        self.0.push(input.to_string());
+    }
+}
+
+impl<'b> CanApply<String> for &'b mut Collector {
+    type Output = String;
+    fn apply(&mut self, input: String) {
+        self.0.push(input);
+    }
+}
+
+impl<'b> CanApply<u64> for &'b mut Collector {
+    type Output = u64;
+    fn apply(&mut self, input: u64) {
+        self.0.push(input.to_string());
+    }
+}